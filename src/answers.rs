@@ -0,0 +1,234 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{day::Part, registry::Outputs};
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AnswersError {
+    pub line: usize,
+    pub expected: &'static str,
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: expected {}", self.line, self.expected)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "fail, expected {expected}"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Known-good answers for the real puzzle inputs, stored as a small subset of TOML:
+///
+/// ```toml
+/// [day_01]
+/// part_1 = "64929"
+/// part_2 = "193697"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<usize, Outputs>,
+}
+
+impl Answers {
+    pub fn parse(raw: &str) -> Result<Self, AnswersError> {
+        let mut answers = Self::default();
+        let mut current = None;
+
+        for (i, line) in raw.lines().enumerate() {
+            let error = |expected| AnswersError {
+                line: i + 1,
+                expected,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let day = header
+                    .strip_suffix(']')
+                    .and_then(|header| header.strip_prefix("day_"))
+                    .and_then(|day| day.parse().ok())
+                    .ok_or_else(|| error("a header such as [day_01]"))?;
+
+                answers.days.entry(day).or_default();
+                current = Some(day);
+                continue;
+            }
+
+            let day = current.ok_or_else(|| error("a [day_NN] header before any answers"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("`part_1 = \"...\"` or `part_2 = \"...\"`"))?;
+            let value = unquote(value.trim()).ok_or_else(|| error("a quoted string"))?;
+
+            let outputs = answers.days.get_mut(&day).unwrap();
+            match key.trim() {
+                "part_1" => outputs.part_1 = Some(value),
+                "part_2" => outputs.part_2 = Some(value),
+                _ => return Err(error("part_1 or part_2")),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn check(&self, day: usize, part: Part, output: &str) -> Verdict {
+        let expected = self.days.get(&day).and_then(|outputs| match part {
+            Part::One => outputs.part_1.as_ref(),
+            Part::Two => outputs.part_2.as_ref(),
+        });
+
+        match expected {
+            Some(expected) if expected == output => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Stores any parts present in `outputs`, keeping the existing answer for the others.
+    pub fn record(&mut self, day: usize, outputs: Outputs) {
+        let answers = self.days.entry(day).or_default();
+
+        if outputs.part_1.is_some() {
+            answers.part_1 = outputs.part_1;
+        }
+        if outputs.part_2.is_some() {
+            answers.part_2 = outputs.part_2;
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (day, outputs)) in self.days.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "[day_{day:02}]")?;
+            for (key, value) in [("part_1", &outputs.part_1), ("part_2", &outputs.part_2)] {
+                if let Some(value) = value {
+                    writeln!(f, "{key} = {}", quote(value))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+fn unquote(raw: &str) -> Option<String> {
+    let mut chars = raw.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut value = String::new();
+
+    while let Some(c) = chars.next() {
+        value.push(match c {
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                c @ ('\\' | '"') => c,
+                _ => return None,
+            },
+            '"' => return None,
+            c => c,
+        });
+    }
+
+    Some(value)
+}
+
+#[test]
+fn test() {
+    let raw = "# Answers for the real inputs
+[day_01]
+part_1 = \"64929\"
+part_2 = \"193697\"
+
+[day_10]
+part_2 = \"#..#\\n\\\"quoted\\\"\"
+";
+
+    let answers = Answers::parse(raw).unwrap();
+    assert_eq!(answers.check(1, Part::One, "64929"), Verdict::Pass);
+    assert_eq!(
+        answers.check(1, Part::Two, "1"),
+        Verdict::Fail {
+            expected: "193697".to_string()
+        }
+    );
+    assert_eq!(answers.check(10, Part::One, "1"), Verdict::Unknown);
+    assert_eq!(
+        answers.check(10, Part::Two, "#..#\n\"quoted\""),
+        Verdict::Pass
+    );
+    assert_eq!(answers.check(2, Part::One, "1"), Verdict::Unknown);
+
+    // Round trips, aside from the comment
+    assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+
+    assert_eq!(
+        Answers::parse("part_1 = \"1\""),
+        Err(AnswersError {
+            line: 1,
+            expected: "a [day_NN] header before any answers"
+        })
+    );
+    assert_eq!(
+        Answers::parse("[day_01]\npart_3 = \"1\"").map_err(|e| e.line),
+        Err(2)
+    );
+    assert!(Answers::parse("[day_01]\npart_1 = 1").is_err());
+}
+
+#[test]
+fn test_record() {
+    let mut answers = Answers::default();
+    answers.record(
+        5,
+        Outputs {
+            part_1: Some("CMZ".to_string()),
+            part_2: Some("MCD".to_string()),
+        },
+    );
+    answers.record(
+        5,
+        Outputs {
+            part_1: None,
+            part_2: Some("MCX".to_string()),
+        },
+    );
+
+    assert_eq!(
+        answers.to_string(),
+        "[day_05]\npart_1 = \"CMZ\"\npart_2 = \"MCX\"\n"
+    );
+}
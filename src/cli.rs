@@ -0,0 +1,457 @@
+use std::{fmt::Display, path::PathBuf};
+
+use crate::{answers, day::Part};
+
+pub const USAGE: &str =
+    "Usage: aoc run <days> [--part <1|2>] [--input <path>] [--param <name=value>]...
+               [--answers <path>]
+       aoc record <days> [--part <1|2>] [--answers <path>]
+       aoc bench <days> [--part <1|2>] [--input <path>] [--param <name=value>]...
+                 [--iterations <n>]
+       aoc mesh [--input <path>] [--format <obj|stl>] [--pockets <include|exclude>]
+       aoc list
+
+Days can be a single day (12), a range (5..9 or 5..=9), a comma separated
+list of either (1,3,5..=7), or `all`. Running `aoc` with no arguments runs
+every day. Benchmarks default to 10 iterations of each stage.
+
+Some days take parameters from the puzzle text, which default to those of the
+real puzzle. These can be overridden when running a single day, such as
+`aoc run 15 --input example.txt --param row=10 --param bound=20`.
+
+Results for the real inputs are checked against the answers file (answers.toml
+by default), and `record` saves the current results to it as known-good.
+
+`mesh` prints the exposed faces of day 18's droplet as a Wavefront OBJ (the
+default) or ASCII STL file, to be opened in any 3D viewer. Faces against air
+pockets inside the droplet are included unless `--pockets exclude` is given.";
+
+const DEFAULT_ITERATIONS: usize = 10;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Days(Vec<usize>),
+}
+
+impl DaySelection {
+    pub fn resolve(&self, available: &[usize]) -> Vec<usize> {
+        match self {
+            DaySelection::All => available.to_vec(),
+            DaySelection::Days(days) => days.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunOptions {
+    pub days: DaySelection,
+    pub part: Option<Part>,
+    pub input: Option<PathBuf>,
+    pub params: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeshFormat {
+    Obj,
+    Stl,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        options: RunOptions,
+        answers: PathBuf,
+    },
+    Record {
+        options: RunOptions,
+        answers: PathBuf,
+    },
+    Bench {
+        options: RunOptions,
+        iterations: usize,
+    },
+    Mesh {
+        input: Option<PathBuf>,
+        format: MeshFormat,
+        /// Whether faces against air trapped inside the droplet are drawn.
+        include_pockets: bool,
+    },
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownFlag(String),
+    MissingValue(&'static str),
+    InvalidDays(String),
+    InvalidPart(String),
+    InvalidIterations(String),
+    InvalidParam(String),
+    InvalidFormat(String),
+    InvalidPockets(String),
+    UnexpectedArgument(String),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::UnknownCommand(command) => write!(f, "unknown command `{command}`"),
+            CliError::UnknownFlag(flag) => write!(f, "unknown flag `{flag}`"),
+            CliError::MissingValue(flag) => write!(f, "missing value for `{flag}`"),
+            CliError::InvalidDays(days) => write!(f, "invalid day selection `{days}`"),
+            CliError::InvalidPart(part) => write!(f, "invalid part `{part}`, expected 1 or 2"),
+            CliError::InvalidIterations(iterations) => write!(
+                f,
+                "invalid iterations `{iterations}`, expected a positive number"
+            ),
+            CliError::InvalidParam(param) => {
+                write!(f, "invalid parameter `{param}`, expected name=value")
+            }
+            CliError::InvalidFormat(format) => {
+                write!(f, "invalid format `{format}`, expected obj or stl")
+            }
+            CliError::InvalidPockets(pockets) => {
+                write!(
+                    f,
+                    "invalid pockets `{pockets}`, expected include or exclude"
+                )
+            }
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument `{arg}`"),
+        }
+    }
+}
+
+fn parse_days(raw: &str) -> Result<DaySelection, CliError> {
+    if raw == "all" {
+        return Ok(DaySelection::All);
+    }
+
+    let invalid = || CliError::InvalidDays(raw.to_string());
+    let parse_day = |day: &str| day.parse::<usize>().map_err(|_| invalid());
+
+    let mut days = Vec::new();
+    for part in raw.split(',') {
+        if let Some((start, end)) = part.split_once("..=") {
+            days.extend(parse_day(start)?..=parse_day(end)?);
+        } else if let Some((start, end)) = part.split_once("..") {
+            days.extend(parse_day(start)?..parse_day(end)?);
+        } else {
+            days.push(parse_day(part)?);
+        }
+    }
+
+    if days.is_empty() {
+        return Err(invalid());
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    Ok(DaySelection::Days(days))
+}
+
+/// Every flag accepted by any command, each of which checks for the ones it doesn't support.
+struct Flags {
+    options: RunOptions,
+    iterations: Option<usize>,
+    answers: Option<PathBuf>,
+}
+
+fn parse_flags(mut args: impl Iterator<Item = String>) -> Result<Flags, CliError> {
+    let mut days = None;
+    let mut part = None;
+    let mut input = None;
+    let mut iterations = None;
+    let mut answers = None;
+    let mut params = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let raw = args.next().ok_or(CliError::MissingValue("--part"))?;
+                part = Some(match raw.as_str() {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => return Err(CliError::InvalidPart(raw)),
+                });
+            }
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or(CliError::MissingValue("--input"))?.into());
+            }
+            "--iterations" | "-n" => {
+                let raw = args.next().ok_or(CliError::MissingValue("--iterations"))?;
+                iterations = Some(match raw.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(CliError::InvalidIterations(raw)),
+                });
+            }
+            "--answers" | "-a" => {
+                answers = Some(
+                    args.next()
+                        .ok_or(CliError::MissingValue("--answers"))?
+                        .into(),
+                );
+            }
+            "--param" => {
+                let raw = args.next().ok_or(CliError::MissingValue("--param"))?;
+                let (name, value) = raw
+                    .split_once('=')
+                    .ok_or_else(|| CliError::InvalidParam(raw.clone()))?;
+                params.push((name.to_string(), value.to_string()));
+            }
+            flag if flag.starts_with('-') => return Err(CliError::UnknownFlag(arg)),
+            _ if days.is_none() => days = Some(parse_days(&arg)?),
+            _ => return Err(CliError::UnexpectedArgument(arg)),
+        }
+    }
+
+    Ok(Flags {
+        options: RunOptions {
+            days: days.unwrap_or(DaySelection::All),
+            part,
+            input,
+            params,
+        },
+        iterations,
+        answers,
+    })
+}
+
+fn parse_mesh(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = None;
+    let mut format = MeshFormat::Obj;
+    let mut include_pockets = true;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                input = Some(args.next().ok_or(CliError::MissingValue("--input"))?.into());
+            }
+            "--format" | "-f" => {
+                let raw = args.next().ok_or(CliError::MissingValue("--format"))?;
+                format = match raw.as_str() {
+                    "obj" => MeshFormat::Obj,
+                    "stl" => MeshFormat::Stl,
+                    _ => return Err(CliError::InvalidFormat(raw)),
+                };
+            }
+            "--pockets" => {
+                let raw = args.next().ok_or(CliError::MissingValue("--pockets"))?;
+                include_pockets = match raw.as_str() {
+                    "include" => true,
+                    "exclude" => false,
+                    _ => return Err(CliError::InvalidPockets(raw)),
+                };
+            }
+            flag if flag.starts_with('-') => return Err(CliError::UnknownFlag(arg)),
+            _ => return Err(CliError::UnexpectedArgument(arg)),
+        }
+    }
+
+    Ok(Command::Mesh {
+        input,
+        format,
+        include_pockets,
+    })
+}
+
+fn reject<T>(value: &Option<T>, flag: &str) -> Result<(), CliError> {
+    match value {
+        Some(_) => Err(CliError::UnknownFlag(flag.to_string())),
+        None => Ok(()),
+    }
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    match args.next().as_deref() {
+        None | Some("run") => {
+            let flags = parse_flags(args)?;
+            reject(&flags.iterations, "--iterations")?;
+
+            Ok(Command::Run {
+                options: flags.options,
+                answers: flags.answers.unwrap_or(answers::DEFAULT_PATH.into()),
+            })
+        }
+        Some("record") => {
+            let flags = parse_flags(args)?;
+            reject(&flags.iterations, "--iterations")?;
+            // Answers are only ever recorded for the real inputs
+            reject(&flags.options.input, "--input")?;
+            if !flags.options.params.is_empty() {
+                return Err(CliError::UnknownFlag("--param".to_string()));
+            }
+
+            Ok(Command::Record {
+                options: flags.options,
+                answers: flags.answers.unwrap_or(answers::DEFAULT_PATH.into()),
+            })
+        }
+        Some("bench") => {
+            let flags = parse_flags(args)?;
+            reject(&flags.answers, "--answers")?;
+
+            Ok(Command::Bench {
+                options: flags.options,
+                iterations: flags.iterations.unwrap_or(DEFAULT_ITERATIONS),
+            })
+        }
+        Some("mesh") => parse_mesh(args),
+        Some("list") => Ok(Command::List),
+        Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(command) => Err(CliError::UnknownCommand(command.to_string())),
+    }
+}
+
+#[test]
+fn test_parse_days() {
+    assert_eq!(parse_days("all"), Ok(DaySelection::All));
+    assert_eq!(parse_days("12"), Ok(DaySelection::Days(vec![12])));
+    assert_eq!(parse_days("5..9"), Ok(DaySelection::Days(vec![5, 6, 7, 8])));
+    assert_eq!(
+        parse_days("9,1,5..=7"),
+        Ok(DaySelection::Days(vec![1, 5, 6, 7, 9]))
+    );
+    assert!(parse_days("9..5").is_err());
+    assert!(parse_days("five").is_err());
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+    assert_eq!(
+        args(&[]),
+        Ok(Command::Run {
+            options: RunOptions {
+                days: DaySelection::All,
+                part: None,
+                input: None,
+                params: vec![],
+            },
+            answers: "answers.toml".into(),
+        })
+    );
+    assert_eq!(
+        args(&["run", "5..9", "--part", "2"]),
+        Ok(Command::Run {
+            options: RunOptions {
+                days: DaySelection::Days(vec![5, 6, 7, 8]),
+                part: Some(Part::Two),
+                input: None,
+                params: vec![],
+            },
+            answers: "answers.toml".into(),
+        })
+    );
+    assert_eq!(
+        args(&["run", "12", "--input", "example.txt", "-a", "other.toml"]),
+        Ok(Command::Run {
+            options: RunOptions {
+                days: DaySelection::Days(vec![12]),
+                part: None,
+                input: Some("example.txt".into()),
+                params: vec![],
+            },
+            answers: "other.toml".into(),
+        })
+    );
+    assert_eq!(
+        args(&["record", "3", "-p", "1"]),
+        Ok(Command::Record {
+            options: RunOptions {
+                days: DaySelection::Days(vec![3]),
+                part: Some(Part::One),
+                input: None,
+                params: vec![],
+            },
+            answers: "answers.toml".into(),
+        })
+    );
+    assert_eq!(
+        args(&["record", "3", "--input", "example.txt"]),
+        Err(CliError::UnknownFlag("--input".to_string()))
+    );
+    assert_eq!(
+        args(&["run", "12", "--part", "3"]),
+        Err(CliError::InvalidPart("3".to_string()))
+    );
+    assert_eq!(
+        args(&["bench", "16..=19", "-n", "5"]),
+        Ok(Command::Bench {
+            options: RunOptions {
+                days: DaySelection::Days(vec![16, 17, 18, 19]),
+                part: None,
+                input: None,
+                params: vec![],
+            },
+            iterations: 5,
+        })
+    );
+    assert_eq!(
+        args(&["run", "1", "--iterations", "5"]),
+        Err(CliError::UnknownFlag("--iterations".to_string()))
+    );
+    assert_eq!(
+        args(&["run", "15", "--param", "row=10", "--param", "bound=20"]),
+        Ok(Command::Run {
+            options: RunOptions {
+                days: DaySelection::Days(vec![15]),
+                part: None,
+                input: None,
+                params: vec![
+                    ("row".to_string(), "10".to_string()),
+                    ("bound".to_string(), "20".to_string())
+                ],
+            },
+            answers: "answers.toml".into(),
+        })
+    );
+    assert_eq!(
+        args(&["run", "15", "--param", "row"]),
+        Err(CliError::InvalidParam("row".to_string()))
+    );
+    assert_eq!(
+        args(&["record", "15", "--param", "row=10"]),
+        Err(CliError::UnknownFlag("--param".to_string()))
+    );
+    assert_eq!(
+        args(&["mesh"]),
+        Ok(Command::Mesh {
+            input: None,
+            format: MeshFormat::Obj,
+            include_pockets: true,
+        })
+    );
+    assert_eq!(
+        args(&[
+            "mesh",
+            "-i",
+            "example.txt",
+            "--format",
+            "stl",
+            "--pockets",
+            "exclude"
+        ]),
+        Ok(Command::Mesh {
+            input: Some("example.txt".into()),
+            format: MeshFormat::Stl,
+            include_pockets: false,
+        })
+    );
+    assert_eq!(
+        args(&["mesh", "--format", "ply"]),
+        Err(CliError::InvalidFormat("ply".to_string()))
+    );
+    assert_eq!(
+        args(&["mesh", "18"]),
+        Err(CliError::UnexpectedArgument("18".to_string()))
+    );
+    assert_eq!(
+        args(&["frobnicate"]),
+        Err(CliError::UnknownCommand("frobnicate".to_string()))
+    );
+}
@@ -1,15 +1,91 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::parse::ParseError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParamError {
+    Unknown(String),
+    Invalid {
+        name: String,
+        expected: &'static str,
+    },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Unknown(name) => write!(f, "unknown parameter `{name}`"),
+            ParamError::Invalid { name, expected } => {
+                write!(f, "invalid value for `{name}`, expected {expected}")
+            }
+        }
+    }
+}
+
+/// Values that the puzzle text gives rather than the input, such as which row to check. The
+/// defaults are those of the real puzzle, and are overridden for the examples.
+pub trait Params: Default {
+    /// Overrides the parameter called `name` with its command line form, `value`.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), ParamError>;
+}
+
+impl Params for () {
+    fn set(&mut self, name: &str, _: &str) -> Result<(), ParamError> {
+        Err(ParamError::Unknown(name.to_string()))
+    }
+}
+
+/// Parses a parameter's value for [`Params::set`].
+pub fn param<T: FromStr>(name: &str, value: &str, expected: &'static str) -> Result<T, ParamError> {
+    value.parse().map_err(|_| ParamError::Invalid {
+        name: name.to_string(),
+        expected,
+    })
+}
+
 pub trait Day {
-    type Input: Clone;
-    type Output;
+    const DAY: usize;
+
+    type Input;
+    type Output1;
+    type Output2;
+    type Params: Params;
 
-    fn part_1(input: Self::Input) -> Self::Output;
-    fn part_2(input: Self::Input) -> Self::Output;
+    /// Parts borrow the parsed input, so it is parsed once and shared by both. Any part that
+    /// needs to mutate the input should clone the pieces it changes.
+    fn part_1(input: &Self::Input) -> Self::Output1;
+    fn part_2(input: &Self::Input) -> Self::Output2;
 
-    fn parse(raw: &str) -> Self::Input;
+    /// Parses the puzzle input, keeping any parameters the parts need alongside it.
+    fn parse(raw: &str, params: &Self::Params) -> Result<Self::Input, ParseError>;
+
+    #[cfg(test)]
+    fn run(input: &str) -> Result<(Self::Output1, Self::Output2), ParseError> {
+        Self::run_with(input, &Self::Params::default())
+    }
 
-    fn run(input: &str) -> (Self::Output, Self::Output) {
-        let input = Self::parse(input);
+    #[cfg(test)]
+    fn run_with(
+        input: &str,
+        params: &Self::Params,
+    ) -> Result<(Self::Output1, Self::Output2), ParseError> {
+        let input = Self::parse(input, params)?;
 
-        (Self::part_1(input.clone()), Self::part_2(input))
+        Ok((Self::part_1(&input), Self::part_2(&input)))
     }
 }
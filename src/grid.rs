@@ -0,0 +1,272 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::parse::{ParseError, Source};
+
+/// The location of a cell, counting right and down from the top left of the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A step from one cell to another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Offset {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Offset {
+    pub const UP: Self = Self::new(0, -1);
+    pub const DOWN: Self = Self::new(0, 1);
+    pub const LEFT: Self = Self::new(-1, 0);
+    pub const RIGHT: Self = Self::new(1, 0);
+
+    /// The four cells sharing an edge.
+    pub const ORTHOGONAL: [Self; 4] = [Self::UP, Self::RIGHT, Self::DOWN, Self::LEFT];
+
+    /// The eight cells sharing an edge or a corner.
+    // None of the solved days need diagonal neighbours yet
+    #[allow(dead_code)]
+    pub const ADJACENT: [Self; 8] = [
+        Self::new(-1, -1),
+        Self::UP,
+        Self::new(1, -1),
+        Self::RIGHT,
+        Self::new(1, 1),
+        Self::DOWN,
+        Self::new(-1, 1),
+        Self::LEFT,
+    ];
+
+    pub const fn new(dx: isize, dy: isize) -> Self {
+        Self { dx, dy }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from a block of text with one character per cell. `cell` converts each
+    /// character, returning `None` if it isn't valid, in which case `expected` is reported.
+    pub fn parse(
+        src: &Source,
+        raw: &str,
+        expected: &str,
+        mut cell: impl FnMut(Point, char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = Vec::new();
+
+        for (y, line) in raw.lines().enumerate() {
+            let mut x = 0;
+
+            for (i, c) in line.char_indices() {
+                if width.is_some_and(|width| x >= width) {
+                    return Err(src.error(&line[i..], "the end of the row"));
+                }
+
+                cells.push(
+                    cell(Point::new(x, y), c).ok_or_else(|| src.error(&line[i..], expected))?,
+                );
+                x += 1;
+            }
+
+            match width {
+                None => width = Some(x),
+                Some(width) if x < width => {
+                    return Err(src.error(&line[line.len()..], format!("a row of {width} cells")))
+                }
+                _ => {}
+            }
+        }
+
+        let width = src.require(width.filter(|&width| width > 0), raw, "a grid")?;
+
+        Ok(Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.contains(point)
+            .then(|| &mut self.cells[point.y * self.width + point.x])
+    }
+
+    /// Adds a row to the bottom of the grid, which must be as wide as the grid.
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = T>) {
+        let len = self.cells.len();
+        self.cells.extend(row);
+        assert_eq!(self.cells.len() - len, self.width, "row is the wrong width");
+
+        self.height += 1;
+    }
+
+    /// The cell reached by taking `offset` from `point`, if it is within the grid.
+    pub fn step(&self, point: Point, offset: Offset) -> Option<Point> {
+        Some(Point::new(
+            point.x.checked_add_signed(offset.dx)?,
+            point.y.checked_add_signed(offset.dy)?,
+        ))
+        .filter(|&point| self.contains(point))
+    }
+
+    /// The cells sharing an edge with `point`.
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Offset::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |offset| self.step(point, offset))
+    }
+
+    /// The cells sharing an edge or a corner with `point`.
+    #[allow(dead_code)]
+    pub fn adjacent(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Offset::ADJACENT
+            .into_iter()
+            .filter_map(move |offset| self.step(point, offset))
+    }
+
+    /// Every cell passed when repeatedly taking `offset` from `point` until leaving the grid,
+    /// not including `point` itself.
+    pub fn ray(&self, point: Point, offset: Offset) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(self.step(point, offset), move |&point| {
+            self.step(point, offset)
+        })
+    }
+
+    /// Every point in the grid, a row at a time.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// A grid of the same size, with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the grid"))
+    }
+}
+
+/// Draws each cell in turn, with a line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test() {
+    let parse = |raw| {
+        Grid::parse(&Source::new(0, raw), raw, "a letter", |_, c| {
+            c.is_alphabetic().then_some(c)
+        })
+    };
+
+    let grid = parse("abc\ndef\n").unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[Point::new(2, 1)], 'f');
+    assert_eq!(grid.get(Point::new(3, 0)), None);
+    assert_eq!(grid.to_string(), "abc\ndef");
+
+    let at = |points: Vec<Point>| points.into_iter().map(|p| grid[p]).collect::<String>();
+    assert_eq!(at(grid.neighbours(Point::new(0, 0)).collect()), "bd");
+    assert_eq!(at(grid.adjacent(Point::new(1, 1)).collect()), "abcfd");
+    assert_eq!(
+        at(grid.ray(Point::new(0, 1), Offset::RIGHT).collect()),
+        "ef"
+    );
+    assert_eq!(grid.ray(Point::new(0, 0), Offset::LEFT).count(), 0);
+
+    let location = |raw| parse(raw).map_err(|e| (e.line, e.column, e.expected));
+    assert_eq!(
+        location("ab\nc\n"),
+        Err((2, 2, "a row of 2 cells".to_string()))
+    );
+    assert_eq!(
+        location("ab\ncde\n"),
+        Err((2, 3, "the end of the row".to_string()))
+    );
+    assert_eq!(location("ab\nc!\n"), Err((2, 2, "a letter".to_string())));
+    assert_eq!(location(""), Err((1, 1, "a grid".to_string())));
+}
@@ -0,0 +1,134 @@
+use std::ops::RangeInclusive;
+
+/// A set of integers, stored as the sorted, inclusive ranges that make it up. Ranges never
+/// overlap or touch, as they are merged together.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<(isize, isize)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<isize>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        let mut placed = false;
+
+        for &(s, e) in &self.ranges {
+            if e.saturating_add(1) < start {
+                ranges.push((s, e));
+            } else if end.saturating_add(1) < s {
+                if !placed {
+                    ranges.push((start, end));
+                    placed = true;
+                }
+
+                ranges.push((s, e));
+            } else {
+                // Overlapping or touching, so swallow it
+                start = start.min(s);
+                end = end.max(e);
+            }
+        }
+
+        if !placed {
+            ranges.push((start, end));
+        }
+
+        self.ranges = ranges;
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<isize>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        self.ranges = self
+            .ranges
+            .iter()
+            .flat_map(|&(s, e)| {
+                [
+                    (s, e.min(start.saturating_sub(1))),
+                    (s.max(end.saturating_add(1)), e),
+                ]
+            })
+            .filter(|&(s, e)| s <= e)
+            .collect();
+    }
+
+    #[allow(dead_code)]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.iter().for_each(|range| union.insert(range));
+
+        union
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other.iter().for_each(|range| difference.remove(range));
+
+        difference
+    }
+
+    pub fn contains(&self, n: isize) -> bool {
+        self.ranges.iter().any(|&(s, e)| (s..=e).contains(&n))
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|&(s, e)| s.abs_diff(e) + 1).sum()
+    }
+
+    /// The ranges making up the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<isize>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+}
+
+impl FromIterator<RangeInclusive<isize>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<isize>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+
+        set
+    }
+}
+
+#[test]
+fn test() {
+    let mut set = IntervalSet::from_iter([5..=8, 1..=2, 3..=3, 12..=14]);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=3, 5..=8, 12..=14]);
+    assert_eq!(set.len(), 10);
+    assert!(set.contains(7) && !set.contains(4));
+
+    set.insert(4..=11);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=14]);
+
+    set.remove(3..=5);
+    set.remove(14..=20);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=2, 6..=13]);
+
+    let other = IntervalSet::from_iter([0..=1, 8..=9]);
+    assert_eq!(
+        set.union(&other).iter().collect::<Vec<_>>(),
+        vec![0..=2, 6..=13]
+    );
+    assert_eq!(
+        set.subtract(&other).iter().collect::<Vec<_>>(),
+        vec![2..=2, 6..=7, 10..=13]
+    );
+
+    #[allow(clippy::reversed_empty_ranges)]
+    set.insert(5..=4);
+    assert_eq!(set.len(), 10);
+    assert_eq!(IntervalSet::new().len(), 0);
+}
@@ -1,38 +1,318 @@
-use std::fs::read_to_string;
+use std::{
+    fs::{read_to_string, write},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use day::Day;
+use answers::{Answers, Verdict};
+use cli::{Command, MeshFormat, RunOptions};
+use day::{Day, Part};
+use problems::day_18::{Day18, Pockets};
+use registry::Registry;
 
+mod answers;
+mod cli;
 mod day;
+mod grid;
+mod interval;
+mod mixer;
+mod ocr;
+mod parse;
 mod problems;
+mod registry;
+mod search;
+mod timing;
 
-macro_rules! run_day {
-    ($day_number:literal, $day:path) => {{
-        let input = read_to_string(format!("inputs/day_{:02}.txt", $day_number)).unwrap();
-        type Day = $day;
-        println!("Day {:02}: {:?}", $day_number, Day::run(&input));
-    }};
-}
-
-fn main() {
-    run_day!(1, problems::day_01::Day01);
-    run_day!(2, problems::day_02::Day02);
-    run_day!(3, problems::day_03::Day03);
-    run_day!(4, problems::day_04::Day04);
-    run_day!(5, problems::day_05::Day05);
-    run_day!(6, problems::day_06::Day06);
-    run_day!(7, problems::day_07::Day07);
-    run_day!(8, problems::day_08::Day08);
-    run_day!(9, problems::day_09::Day09);
-    run_day!(10, problems::day_10::Day10);
-    run_day!(11, problems::day_11::Day11);
-    run_day!(12, problems::day_12::Day12);
-    run_day!(13, problems::day_13::Day13);
-    run_day!(14, problems::day_14::Day14);
-    run_day!(15, problems::day_15::Day15);
-    run_day!(16, problems::day_16::Day16);
-    run_day!(17, problems::day_17::Day17);
-    run_day!(18, problems::day_18::Day18);
-    run_day!(19, problems::day_19::Day19);
-    run_day!(20, problems::day_20::Day20);
-    run_day!(21, problems::day_21::Day21);
+/// Resolves the selected days, checking that each has been solved.
+fn select_days(registry: &Registry, options: &RunOptions) -> Option<Vec<usize>> {
+    let days = options.days.resolve(&registry.days());
+
+    if options.input.is_some() && days.len() != 1 {
+        eprintln!("error: `--input` can only be used when running a single day");
+        return None;
+    }
+
+    if !options.params.is_empty() && days.len() != 1 {
+        eprintln!("error: `--param` can only be used when running a single day");
+        return None;
+    }
+
+    if let Some(day_number) = days.iter().find(|&&day| registry.get(day).is_none()) {
+        eprintln!("error: day {day_number:02} has not been solved");
+        return None;
+    }
+
+    Some(days)
+}
+
+fn read_input(input: Option<&Path>, day_number: usize) -> Option<String> {
+    let path = input
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(format!("inputs/day_{:02}.txt", day_number)));
+
+    match read_to_string(&path) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            eprintln!(
+                "error: day {day_number:02}: could not read {}: {e}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+/// Starts multi-line outputs, such as rendered screens, on their own line.
+fn display_output(output: &str) -> String {
+    if output.contains('\n') {
+        format!("\n{output}")
+    } else {
+        format!(" {output}")
+    }
+}
+
+/// Loads the answers file, treating a missing file as having no known answers.
+fn load_answers(path: &Path) -> Option<Answers> {
+    let raw = match read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Some(Answers::default()),
+        Err(e) => {
+            eprintln!("error: could not read {}: {e}", path.display());
+            return None;
+        }
+    };
+
+    Answers::parse(&raw)
+        .map_err(|e| eprintln!("error: {}, {e}", path.display()))
+        .ok()
+}
+
+fn run_days(registry: &Registry, options: RunOptions, answers_path: &Path) -> ExitCode {
+    let Some(days) = select_days(registry, &options) else {
+        return ExitCode::FAILURE;
+    };
+
+    // Answers are only known for the real inputs, with the real puzzle's parameters
+    let answers = if options.input.is_some() || !options.params.is_empty() {
+        None
+    } else {
+        match load_answers(answers_path) {
+            Some(answers) => Some(answers),
+            None => return ExitCode::FAILURE,
+        }
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut rows = Vec::new();
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+
+    for day_number in days {
+        let Some(input) = read_input(options.input.as_deref(), day_number) else {
+            exit_code = ExitCode::FAILURE;
+            continue;
+        };
+
+        match registry
+            .get(day_number)
+            .unwrap()
+            .run(&input, options.part, &options.params)
+        {
+            Ok((outputs, timings)) => {
+                for (part, output) in [(Part::One, outputs.part_1), (Part::Two, outputs.part_2)] {
+                    let Some(output) = output else {
+                        continue;
+                    };
+
+                    let Some(answers) = &answers else {
+                        println!(
+                            "Day {day_number:02} part {part}:{}",
+                            display_output(&output)
+                        );
+                        continue;
+                    };
+
+                    let verdict = answers.check(day_number, part, &output);
+                    match verdict {
+                        Verdict::Pass => passed += 1,
+                        Verdict::Fail { .. } => {
+                            failed += 1;
+                            exit_code = ExitCode::FAILURE;
+                        }
+                        Verdict::Unknown => unknown += 1,
+                    }
+
+                    println!(
+                        "Day {day_number:02} part {part}:{} ({verdict})",
+                        display_output(&output)
+                    );
+                }
+
+                rows.push((day_number, timings));
+            }
+            Err(e) => {
+                eprintln!("{}", e.report());
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if answers.is_some() && !rows.is_empty() {
+        println!("\n{passed} passed, {failed} failed, {unknown} unknown");
+    }
+
+    if !rows.is_empty() {
+        print!("\n{}", timing::timings_table(&rows));
+    }
+
+    exit_code
+}
+
+/// Runs each day against its real input, saving the results as the known-good answers.
+fn record_days(registry: &Registry, options: RunOptions, answers_path: &Path) -> ExitCode {
+    let Some(days) = select_days(registry, &options) else {
+        return ExitCode::FAILURE;
+    };
+
+    let Some(mut answers) = load_answers(answers_path) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut recorded = 0;
+
+    for day_number in days {
+        let Some(input) = read_input(options.input.as_deref(), day_number) else {
+            exit_code = ExitCode::FAILURE;
+            continue;
+        };
+
+        match registry
+            .get(day_number)
+            .unwrap()
+            .run(&input, options.part, &options.params)
+        {
+            Ok((outputs, _)) => {
+                for (part, output) in [(Part::One, &outputs.part_1), (Part::Two, &outputs.part_2)] {
+                    if let Some(output) = output {
+                        println!("Day {day_number:02} part {part}:{}", display_output(output));
+                    }
+                }
+
+                answers.record(day_number, outputs);
+                recorded += 1;
+            }
+            Err(e) => {
+                eprintln!("{}", e.report());
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if recorded > 0 {
+        if let Err(e) = write(answers_path, answers.to_string()) {
+            eprintln!("error: could not write {}: {e}", answers_path.display());
+            return ExitCode::FAILURE;
+        }
+
+        println!(
+            "\nRecorded answers for {recorded} day(s) to {}",
+            answers_path.display()
+        );
+    }
+
+    exit_code
+}
+
+fn bench_days(registry: &Registry, options: RunOptions, iterations: usize) -> ExitCode {
+    let Some(days) = select_days(registry, &options) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut rows = Vec::new();
+
+    for day_number in days {
+        let Some(input) = read_input(options.input.as_deref(), day_number) else {
+            exit_code = ExitCode::FAILURE;
+            continue;
+        };
+
+        match registry.get(day_number).unwrap().bench(
+            &input,
+            options.part,
+            &options.params,
+            iterations,
+        ) {
+            Ok(benchmark) => rows.push((day_number, benchmark)),
+            Err(e) => {
+                eprintln!("{}", e.report());
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if !rows.is_empty() {
+        print!("{}", timing::benchmark_table(&rows, iterations));
+    }
+
+    exit_code
+}
+
+/// Prints day 18's droplet as a mesh, to check what its surface area counts.
+fn export_mesh(input: Option<PathBuf>, format: MeshFormat, include_pockets: bool) -> ExitCode {
+    let Some(input) = read_input(input.as_deref(), Day18::DAY) else {
+        return ExitCode::FAILURE;
+    };
+
+    match Day18::parse(&input, &()) {
+        Ok(droplet) => {
+            let mesh = droplet.mesh(if include_pockets {
+                Pockets::Include
+            } else {
+                Pockets::Exclude
+            });
+            match format {
+                MeshFormat::Obj => print!("{}", mesh.obj()),
+                MeshFormat::Stl => print!("{}", mesh.stl()),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e.report());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let registry = problems::registry();
+
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run { options, answers }) => run_days(&registry, options, &answers),
+        Ok(Command::Record { options, answers }) => record_days(&registry, options, &answers),
+        Ok(Command::Bench {
+            options,
+            iterations,
+        }) => bench_days(&registry, options, iterations),
+        Ok(Command::Mesh {
+            input,
+            format,
+            include_pockets,
+        }) => export_mesh(input, format, include_pockets),
+        Ok(Command::List) => {
+            for runner in registry.iter() {
+                println!("Day {:02}", runner.day());
+            }
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            ExitCode::from(2)
+        }
+    }
 }
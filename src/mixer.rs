@@ -0,0 +1,148 @@
+/// A circular sequence where any element can be moved along by an offset, as if it were taken
+/// out and the rest of the sequence closed up around it. Elements are identified by their
+/// original index, and are kept in blocks of roughly √n, so each move costs O(√n).
+pub struct CircularMixer {
+    values: Vec<isize>,
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>,
+    block_size: usize,
+    moves_since_rebalance: usize,
+}
+
+impl CircularMixer {
+    pub fn new(values: Vec<isize>) -> Self {
+        let block_size = (values.len() as f64).sqrt().ceil().max(1.0) as usize;
+
+        let mut mixer = Self {
+            blocks: vec![(0..values.len()).collect()],
+            block_of: vec![0; values.len()],
+            values,
+            block_size,
+            moves_since_rebalance: 0,
+        };
+        mixer.rebalance();
+
+        mixer
+    }
+
+    /// Evens the blocks back out, as moves leave some of them larger than others.
+    fn rebalance(&mut self) {
+        let order = self.blocks.concat();
+        self.blocks = order
+            .chunks(self.block_size)
+            .map(|block| block.to_vec())
+            .collect();
+
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &id in block {
+                self.block_of[id] = b;
+            }
+        }
+
+        self.moves_since_rebalance = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The current position of the element that started at `id`.
+    pub fn position(&self, id: usize) -> usize {
+        let b = self.block_of[id];
+
+        self.blocks[..b].iter().map(Vec::len).sum::<usize>()
+            + self.blocks[b]
+                .iter()
+                .position(|&other| other == id)
+                .unwrap()
+    }
+
+    /// Finds the block, and the index within it, of a position in the sequence.
+    fn locate(&self, mut position: usize) -> (usize, usize) {
+        for (b, block) in self.blocks.iter().enumerate() {
+            if position < block.len() {
+                return (b, position);
+            }
+
+            position -= block.len();
+        }
+
+        unreachable!("position is past the end of the sequence")
+    }
+
+    /// The value at `position`, wrapping around the end of the sequence.
+    pub fn get(&self, position: usize) -> isize {
+        let (b, i) = self.locate(position % self.len());
+
+        self.values[self.blocks[b][i]]
+    }
+
+    /// Moves the element that started at `id` along by `offset`, backwards if it is negative.
+    pub fn shift(&mut self, id: usize, offset: isize) {
+        if self.len() < 2 {
+            return;
+        }
+
+        let position = self.position(id);
+        let (b, i) = self.locate(position);
+        self.blocks[b].remove(i);
+
+        // Only the other elements are passed, so there is one fewer place to wrap around
+        let target = (position as isize + offset).rem_euclid(self.len() as isize - 1) as usize;
+        let (b, i) = self.locate(target);
+        self.blocks[b].insert(i, id);
+        self.block_of[id] = b;
+
+        self.moves_since_rebalance += 1;
+        if self.moves_since_rebalance >= self.block_size {
+            self.rebalance();
+        }
+    }
+
+    /// Moves every element along by its own value, in their original order.
+    pub fn mix(&mut self) {
+        for id in 0..self.len() {
+            self.shift(id, self.values[id]);
+        }
+    }
+}
+
+#[test]
+fn test() {
+    // Reads the whole circle, starting from wherever the first element ended up
+    let from_first = |mixer: &CircularMixer| {
+        let first = mixer.position(0);
+        (0..mixer.len())
+            .map(|i| mixer.get(first + i))
+            .collect::<Vec<_>>()
+    };
+
+    let values = vec![1, 2, -3, 3, -2, 0, 4];
+    let mut mixer = CircularMixer::new(values.clone());
+    mixer.mix();
+    assert_eq!(from_first(&mixer), vec![1, 2, -3, 4, 0, 3, -2]);
+
+    // Compare against moving elements around a plain Vec
+    let values = (0..200)
+        .map(|i: isize| (i * 7919 % 401) - 200)
+        .collect::<Vec<_>>();
+    let mut expected = (0..values.len()).collect::<Vec<_>>();
+    let mut mixer = CircularMixer::new(values.clone());
+
+    for _ in 0..3 {
+        mixer.mix();
+
+        for (id, &offset) in values.iter().enumerate() {
+            let position = expected.iter().position(|&other| other == id).unwrap();
+            expected.remove(position);
+            let target = (position as isize + offset).rem_euclid(expected.len() as isize);
+            expected.insert(target as usize, id);
+        }
+    }
+
+    let first = expected.iter().position(|&id| id == 0).unwrap();
+    let expected = (0..expected.len())
+        .map(|i| values[expected[(first + i) % expected.len()]])
+        .collect::<Vec<_>>();
+    assert_eq!(from_first(&mixer), expected);
+}
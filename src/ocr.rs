@@ -0,0 +1,93 @@
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+
+/// The 4x6 font that puzzles draw their answers in.
+const FONT: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn on a grid of lit pixels, where each letter is followed by a blank
+/// column. Returns `None` if the grid holds anything other than recognised letters.
+pub fn read<R: AsRef<[bool]>>(pixels: &[R]) -> Option<String> {
+    if pixels.len() != GLYPH_HEIGHT {
+        return None;
+    }
+
+    let width = pixels.iter().map(|row| row.as_ref().len()).max()?;
+    let pixel = |x: usize, y: usize| pixels[y].as_ref().get(x).copied().unwrap_or(false);
+
+    (0..width)
+        .step_by(GLYPH_WIDTH + 1)
+        .map(|left| {
+            let gap_is_blank = (0..GLYPH_HEIGHT).all(|y| !pixel(left + GLYPH_WIDTH, y));
+
+            FONT.iter()
+                .find(|(_, glyph)| {
+                    glyph.iter().enumerate().all(|(y, row)| {
+                        row.chars()
+                            .enumerate()
+                            .all(|(x, c)| pixel(left + x, y) == (c == '#'))
+                    })
+                })
+                .filter(|_| gap_is_blank)
+                .map(|&(letter, _)| letter)
+        })
+        .collect()
+}
+
+#[test]
+fn test() {
+    let parse = |screen: &str| {
+        screen
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+
+    let screen = "####.####..##..####.####.#....#..#.####.
+#....#....#..#....#.#....#....#..#.#....
+###..###..#......#..###..#....####.###..
+#....#....#.....#...#....#....#..#.#....
+#....#....#..#.#....#....#....#..#.#....
+#....####..##..####.####.####.#..#.####.";
+    assert_eq!(read(&parse(screen)), Some("FECZELHE".to_string()));
+
+    // Every glyph in the font can be read back
+    let alphabet = (0..GLYPH_HEIGHT)
+        .map(|y| {
+            FONT.iter()
+                .map(|(_, glyph)| format!("{}.", glyph[y]))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(
+        read(&parse(&alphabet)),
+        Some(FONT.iter().map(|&(letter, _)| letter).collect())
+    );
+
+    let stripes = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+    assert_eq!(read(&parse(stripes)), None);
+    assert_eq!(read(&parse("####")), None);
+}
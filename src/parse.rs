@@ -0,0 +1,126 @@
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// Renders the error along with the offending line, and a marker under the failing column.
+    pub fn report(&self) -> String {
+        let gutter = self.line.to_string().len();
+
+        format!(
+            "error: {self}\n{:gutter$} |\n{} | {}\n{:gutter$} | {:>column$}",
+            "",
+            self.line,
+            self.source_line,
+            "",
+            "^",
+            column = self.column
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {:02}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+/// Raw puzzle input, used to locate any errors encountered whilst parsing it.
+#[derive(Clone, Copy)]
+pub struct Source<'a> {
+    day: usize,
+    raw: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(day: usize, raw: &'a str) -> Self {
+        Self { day, raw }
+    }
+
+    /// Creates an error located at the start of `at`, which must be a slice of the raw input.
+    /// Anything else will be reported at the end of the input.
+    pub fn error(&self, at: &str, expected: impl Display) -> ParseError {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(self.raw.as_ptr() as usize)
+            .filter(|&offset| self.raw.is_char_boundary(offset))
+            .unwrap_or(self.raw.len())
+            // Errors at the end of the input belong on the last line, not after it
+            .min(self.raw.trim_end_matches('\n').len());
+
+        let before = &self.raw[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        ParseError {
+            day: self.day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.to_string(),
+            source_line: self.raw[line_start..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    /// Unwraps `value`, otherwise reports an error at the end of `within`.
+    pub fn require<T>(
+        &self,
+        value: Option<T>,
+        within: &str,
+        expected: impl Display,
+    ) -> Result<T, ParseError> {
+        value.ok_or_else(|| self.error(&within[within.len()..], expected))
+    }
+
+    pub fn number<T: FromStr>(&self, raw: &str) -> Result<T, ParseError> {
+        raw.parse().map_err(|_| self.error(raw, "a number"))
+    }
+
+    pub fn split_once<'b>(
+        &self,
+        raw: &'b str,
+        delimiter: &str,
+    ) -> Result<(&'b str, &'b str), ParseError> {
+        self.require(raw.split_once(delimiter), raw, format!("{delimiter:?}"))
+    }
+
+    pub fn strip_prefix<'b>(&self, raw: &'b str, prefix: &str) -> Result<&'b str, ParseError> {
+        raw.strip_prefix(prefix)
+            .ok_or_else(|| self.error(raw, format!("{prefix:?}")))
+    }
+}
+
+#[test]
+fn test_location() {
+    let raw = "1 2\n3 x 4\n";
+    let src = Source::new(1, raw);
+
+    let line = raw.lines().nth(1).unwrap();
+    let error = src.number::<usize>(&line[2..3]).unwrap_err();
+
+    assert_eq!((error.line, error.column), (2, 3));
+    assert_eq!(error.source_line, "3 x 4");
+    assert_eq!(
+        error.report(),
+        "error: day 01, line 2, column 3: expected a number
+  |
+2 | 3 x 4
+  |   ^"
+    );
+
+    let error = src.split_once(line, ",").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 6));
+    assert_eq!(error.expected, "\",\"");
+}
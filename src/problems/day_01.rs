@@ -1,4 +1,7 @@
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 type Input = Vec<Vec<usize>>;
 
@@ -9,30 +12,36 @@ impl Day01 {
     }
 }
 impl Day for Day01 {
+    const DAY: usize = 1;
+
     type Input = Input;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        *Self::sum_elves(&input).iter().max().unwrap()
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        *Self::sum_elves(input).iter().max().unwrap()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        let mut elves = Self::sum_elves(&input);
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        let mut elves = Self::sum_elves(input);
         elves.sort_unstable();
         elves[elves.len() - 3..elves.len()].iter().sum()
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.lines().fold(vec![vec![]], |mut elves, line| {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        raw.lines().try_fold(vec![vec![]], |mut elves, line| {
             if line.is_empty() {
                 // Start a new elf
                 elves.push(Vec::new());
             } else {
                 // Add the value to the last elf
-                elves.last_mut().unwrap().push(line.parse().unwrap());
+                elves.last_mut().unwrap().push(src.number(line)?);
             }
 
-            elves
+            Ok(elves)
         })
     }
 }
@@ -54,5 +63,5 @@ fn test() {
 
 10000";
 
-    assert_eq!(Day01::run(input), (24000, 45000));
+    assert_eq!(Day01::run(input), Ok((24000, 45000)));
 }
@@ -1,4 +1,7 @@
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 use std::collections::HashMap;
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -18,7 +21,7 @@ impl Outcome {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Choice {
     Rock,
     Paper,
@@ -48,22 +51,12 @@ impl Choice {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub enum UnknownInput {
     X,
     Y,
     Z,
 }
-impl From<char> for UnknownInput {
-    fn from(c: char) -> Self {
-        match c {
-            'A' | 'X' => Self::X,
-            'B' | 'Y' => Self::Y,
-            'C' | 'Z' => Self::Z,
-            _ => unreachable!(),
-        }
-    }
-}
 impl From<UnknownInput> for Choice {
     fn from(c: UnknownInput) -> Self {
         match c {
@@ -86,20 +79,24 @@ impl From<UnknownInput> for Outcome {
 pub struct Day02;
 
 impl Day for Day02 {
+    const DAY: usize = 2;
+
     type Input = Vec<(Choice, UnknownInput)>;
 
-    type Output = u32;
+    type Output1 = u32;
+    type Output2 = u32;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         input
-            .into_iter()
-            .map(|game| (game.0, Choice::from(game.1)))
+            .iter()
+            .map(|&(opponent, input)| (opponent, Choice::from(input)))
             .fold(0, |score, game| {
                 score + game.1.score() + game.1.outcome(&game.0).score()
             })
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
         let solutions = {
             let mut solutions = HashMap::new();
 
@@ -119,20 +116,34 @@ impl Day for Day02 {
         };
 
         input
-            .into_iter()
-            .map(|game| (game.0, Outcome::from(game.1)))
+            .iter()
+            .map(|&(opponent, input)| (opponent, Outcome::from(input)))
             .fold(0, |score, game| {
                 score + game.1.score() + solutions.get(&game).unwrap().score()
             })
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         raw.lines()
             .map(|line| {
-                (
-                    Choice::from(UnknownInput::from(line.chars().next().unwrap())),
-                    UnknownInput::from(line.chars().last().unwrap()),
-                )
+                let (opponent, unknown) = src.split_once(line, " ")?;
+
+                Ok((
+                    match opponent {
+                        "A" => Choice::Rock,
+                        "B" => Choice::Paper,
+                        "C" => Choice::Scissors,
+                        _ => return Err(src.error(opponent, "A, B or C")),
+                    },
+                    match unknown {
+                        "X" => UnknownInput::X,
+                        "Y" => UnknownInput::Y,
+                        "Z" => UnknownInput::Z,
+                        _ => return Err(src.error(unknown, "X, Y or Z")),
+                    },
+                ))
             })
             .collect()
     }
@@ -144,5 +155,5 @@ fn test() {
 B X
 C Z";
 
-    assert_eq!(Day02::run(input), (15, 12));
+    assert_eq!(Day02::run(input), Ok((15, 12)));
 }
@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 fn get_score(c: char) -> u32 {
     match c {
@@ -12,15 +15,19 @@ fn get_score(c: char) -> u32 {
 
 pub struct Day03;
 impl Day for Day03 {
+    const DAY: usize = 3;
+
     type Input = Vec<(Vec<char>, Vec<char>)>;
-    type Output = u32;
+    type Output1 = u32;
+    type Output2 = u32;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         input
-            .into_iter()
-            .map(|rucksack| {
-                let a: HashSet<char> = HashSet::from_iter(rucksack.0.into_iter());
-                let b: HashSet<char> = HashSet::from_iter(rucksack.1.into_iter());
+            .iter()
+            .map(|(a, b)| {
+                let a: HashSet<char> = a.iter().copied().collect();
+                let b: HashSet<char> = b.iter().copied().collect();
 
                 let common = *a.intersection(&b).next().unwrap();
                 get_score(common)
@@ -28,12 +35,10 @@ impl Day for Day03 {
             .sum()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
         input
-            .into_iter()
-            .map(|rucksack| {
-                HashSet::from_iter(rucksack.0.into_iter().chain(rucksack.1.into_iter()))
-            })
+            .iter()
+            .map(|(a, b)| a.iter().chain(b).copied().collect())
             .collect::<Vec<_>>()
             .chunks(3)
             .map(|group: &[HashSet<char>]| {
@@ -46,15 +51,29 @@ impl Day for Day03 {
             .sum()
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         raw.lines()
             .map(|rucksack| {
-                let half = rucksack.chars().count() / 2;
+                if let Some((i, _)) = rucksack
+                    .char_indices()
+                    .find(|(_, c)| !c.is_ascii_alphabetic())
+                {
+                    return Err(src.error(&rucksack[i..], "an item (a-z or A-Z)"));
+                }
+
+                let item_count = rucksack.len();
+                if !item_count.is_multiple_of(2) {
+                    return Err(src.error(&rucksack[item_count..], "an even number of items"));
+                }
+
+                let half = item_count / 2;
 
-                (
+                Ok((
                     Vec::from_iter(rucksack.chars().take(half)),
                     Vec::from_iter(rucksack.chars().skip(half)),
-                )
+                ))
             })
             .collect()
     }
@@ -68,5 +87,5 @@ PmmdzqPrVvPwwTWBwg
 wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw";
-    assert_eq!(Day03::run(input), (157, 70));
+    assert_eq!(Day03::run(input), Ok((157, 70)));
 }
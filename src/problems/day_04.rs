@@ -1,24 +1,31 @@
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 #[derive(Clone)]
 pub struct Assignment(usize, usize);
-impl From<&str> for Assignment {
-    fn from(s: &str) -> Self {
-        s.split_once('-')
-            .map(|(start, end)| Assignment(start.parse().unwrap(), end.parse().unwrap()))
-            .unwrap()
+impl Assignment {
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
+        let (start, end) = src.split_once(raw, "-")?;
+
+        Ok(Assignment(src.number(start)?, src.number(end)?))
     }
 }
 
 pub struct Day04;
 impl Day for Day04 {
+    const DAY: usize = 4;
+
     type Input = Vec<(Assignment, Assignment)>;
 
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         input
-            .into_iter()
+            .iter()
             .filter(|(a, b)| {
                 // Find the largest rang
                 let (largest, smallest) = if a.1 - a.0 > b.1 - b.0 {
@@ -32,18 +39,20 @@ impl Day for Day04 {
             .count()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
         input
-            .into_iter()
+            .iter()
             .filter(|(a, b)| (a.1 >= b.0 && a.0 <= b.0) || (b.1 >= a.0 && b.0 <= a.0))
             .count()
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         raw.lines()
             .map(|line| {
-                let (a, b) = line.split_once(',').unwrap();
-                (Assignment::from(a), Assignment::from(b))
+                let (a, b) = src.split_once(line, ",")?;
+                Ok((Assignment::parse(&src, a)?, Assignment::parse(&src, b)?))
             })
             .collect()
     }
@@ -56,5 +65,5 @@ fn test() {
 2-8,3-7
 6-6,4-6
 2-6,4-8";
-    assert_eq!(Day04::run(input), (2, 4));
+    assert_eq!(Day04::run(input), Ok((2, 4)));
 }
@@ -1,6 +1,7 @@
-use std::str::SplitWhitespace;
-
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 #[derive(Clone)]
 pub struct Move {
@@ -41,11 +42,15 @@ impl Stacks {
 
 pub struct Day05;
 impl Day for Day05 {
+    const DAY: usize = 5;
+
     type Input = (Stacks, Vec<Move>);
 
-    type Output = String;
+    type Output1 = String;
+    type Output2 = String;
+    type Params = ();
 
-    fn part_1((mut stacks, moves): Self::Input) -> Self::Output {
+    fn part_1((stacks, moves): &Self::Input) -> Self::Output1 {
         struct StackMover3000;
         impl StackMover for StackMover3000 {
             fn pick_up(stack: &mut Stack, amount: usize) -> Stack {
@@ -53,11 +58,12 @@ impl Day for Day05 {
             }
         }
 
-        stacks.execute::<StackMover3000>(&moves);
+        let mut stacks = stacks.clone();
+        stacks.execute::<StackMover3000>(moves);
         stacks.get_tops()
     }
 
-    fn part_2((mut stacks, moves): Self::Input) -> Self::Output {
+    fn part_2((stacks, moves): &Self::Input) -> Self::Output2 {
         struct StackMover3001;
         impl StackMover for StackMover3001 {
             fn pick_up(stack: &mut Stack, amount: usize) -> Stack {
@@ -65,41 +71,43 @@ impl Day for Day05 {
             }
         }
 
-        stacks.execute::<StackMover3001>(&moves);
+        let mut stacks = stacks.clone();
+        stacks.execute::<StackMover3001>(moves);
         stacks.get_tops()
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        let (stacks, moves) = raw.split_once("\n\n").unwrap();
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        let (stacks, moves) = src.require(
+            raw.split_once("\n\n"),
+            raw,
+            "a blank line between the stacks and the moves",
+        )?;
+
+        let mut stacks_raw = stacks.lines().rev();
+        let numbers = src.require(stacks_raw.next(), stacks, "a row of stack numbers")?;
+        let stack_count = src.number(src.require(
+            numbers.split_whitespace().last(),
+            numbers,
+            "a stack number",
+        )?)?;
 
         // Parse stacks
-        let stacks = {
-            let mut stacks_raw = stacks.lines().rev();
-            let stack_count = stacks_raw
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap();
-
-            stacks_raw.fold(Stacks::new(stack_count), |mut stacks, line| {
-                let mut line = line.chars();
-                for stack in stacks.0.iter_mut() {
-                    let c = line.nth(1).unwrap();
-
-                    if !c.is_whitespace() {
-                        stack.insert(0, c);
+        let stacks = stacks_raw.try_fold(Stacks::new(stack_count), |mut stacks, line| {
+            for (i, stack) in stacks.0.iter_mut().enumerate() {
+                // Crates are in the form `[C] `, with blank space for empty positions
+                match line.get(i * 4 + 1..i * 4 + 2) {
+                    None | Some(" ") => {}
+                    Some(c) if c.chars().all(|c| c.is_ascii_alphabetic()) => {
+                        stack.insert(0, c.chars().next().unwrap());
                     }
-
-                    // Skip closing bracket, and next space
-                    line.next();
-                    line.next();
+                    Some(c) => return Err(src.error(c, "a crate letter")),
                 }
-                stacks
-            })
-        };
+            }
+
+            Ok(stacks)
+        })?;
 
         // Parse moves
         let moves = moves
@@ -108,18 +116,21 @@ impl Day for Day05 {
                 let mut words = m.split_whitespace();
 
                 // nth consumes the previous elements
-                let get_num =
-                    |words: &mut SplitWhitespace| words.nth(1).unwrap().parse::<usize>().unwrap();
-
-                Move {
-                    amount: get_num(&mut words),
-                    from: get_num(&mut words) - 1,
-                    to: get_num(&mut words) - 1,
-                }
+                let mut next_number = |expected| src.require(words.nth(1), m, expected);
+                let get_stack = |raw: &str| match src.number::<usize>(raw)? {
+                    stack if (1..=stack_count).contains(&stack) => Ok(stack - 1),
+                    _ => Err(src.error(raw, format!("a stack between 1 and {stack_count}"))),
+                };
+
+                Ok(Move {
+                    amount: src.number(next_number("an amount")?)?,
+                    from: get_stack(next_number("a stack to move from")?)?,
+                    to: get_stack(next_number("a stack to move to")?)?,
+                })
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        (stacks, moves)
+        Ok((stacks, moves))
     }
 }
 
@@ -135,5 +146,8 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
 
-    assert_eq!(Day05::run(input), ("CMZ".to_string(), "MCD".to_string()));
+    assert_eq!(
+        Day05::run(input),
+        Ok(("CMZ".to_string(), "MCD".to_string()))
+    );
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::day::Day;
+use crate::{day::Day, parse::ParseError};
 
 fn find_sequence(s: &Vec<char>, n: usize) -> usize {
     if s.len() > n {
@@ -16,28 +16,35 @@ fn find_sequence(s: &Vec<char>, n: usize) -> usize {
 
 pub struct Day06;
 impl Day for Day06 {
+    const DAY: usize = 6;
+
     type Input = Vec<char>;
 
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        find_sequence(&input, 4)
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        find_sequence(input, 4)
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        find_sequence(&input, 14)
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        find_sequence(input, 14)
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.chars().collect()
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        Ok(raw.chars().collect())
     }
 }
 
 #[test]
 fn test() {
-    assert_eq!(Day06::run("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), (7, 19));
-    assert_eq!(Day06::run("bvwbjplbgvbhsrlpgdmjqwftvncz"), (5, 23));
-    assert_eq!(Day06::run("nppdvjthqldpwncqszvftbrmjlhg"), (6, 23));
-    assert_eq!(Day06::run("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), (10, 29));
-    assert_eq!(Day06::run("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), (11, 26));
+    assert_eq!(Day06::run("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Ok((7, 19)));
+    assert_eq!(Day06::run("bvwbjplbgvbhsrlpgdmjqwftvncz"), Ok((5, 23)));
+    assert_eq!(Day06::run("nppdvjthqldpwncqszvftbrmjlhg"), Ok((6, 23)));
+    assert_eq!(
+        Day06::run("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"),
+        Ok((10, 29))
+    );
+    assert_eq!(Day06::run("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), Ok((11, 26)));
 }
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 const MAX_DIR_SIZE: usize = 100000;
 const DISK_SIZE: usize = 70000000;
@@ -8,19 +11,23 @@ const UPDATE_SIZE: usize = 30000000;
 
 pub struct Day07;
 impl Day for Day07 {
+    const DAY: usize = 7;
+
     type Input = HashMap<String, usize>;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         // Find directories under MAX_DIR_SIZE
-        input.into_values().filter(|&dir| dir <= MAX_DIR_SIZE).sum()
+        input.values().filter(|&&dir| dir <= MAX_DIR_SIZE).sum()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
         let root_size = *input.get("/").unwrap();
         let remaining_size = DISK_SIZE - root_size;
 
-        input.into_values().fold(root_size, |smallest, dir| {
+        input.values().fold(root_size, |smallest, &dir| {
             if remaining_size + dir >= UPDATE_SIZE && dir < smallest {
                 dir
             } else {
@@ -29,14 +36,18 @@ impl Day for Day07 {
         })
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         let mut path = Vec::new();
         let mut dirs = HashMap::new();
 
         let mut lines = raw.lines().peekable();
         while let Some(line) = lines.next() {
-            match &line[2..4] {
-                "cd" => match &line[5..] {
+            let command = src.strip_prefix(line, "$ ")?;
+
+            match command.split_once(' ') {
+                Some(("cd", dir)) => match dir {
                     ".." => {
                         path.pop();
                     }
@@ -45,7 +56,7 @@ impl Day for Day07 {
                         path.push(p.to_string());
                     }
                 },
-                "ls" => {
+                None if command == "ls" => {
                     // Add file sizes to current directory
                     let mut dir_size = 0;
                     while {
@@ -55,10 +66,15 @@ impl Day for Day07 {
                             false
                         }
                     } {
-                        let size_or_type = lines.next().unwrap().split_whitespace().next().unwrap();
+                        let entry = lines.next().unwrap();
+                        let size_or_type = src.require(
+                            entry.split_whitespace().next(),
+                            entry,
+                            "a file size or `dir`",
+                        )?;
 
                         if size_or_type != "dir" {
-                            dir_size += size_or_type.parse::<usize>().unwrap();
+                            dir_size += src.number::<usize>(size_or_type)?;
                         }
                     }
 
@@ -68,11 +84,11 @@ impl Day for Day07 {
                         *dirs.entry(path).or_insert(0) += dir_size;
                     }
                 }
-                _ => unreachable!(),
+                _ => return Err(src.error(command, "`cd <dir>` or `ls`")),
             }
         }
 
-        dirs
+        Ok(dirs)
     }
 }
 
@@ -102,5 +118,5 @@ $ ls
 5626152 d.ext
 7214296 k";
 
-    assert_eq!(Day07::run(input), (95437, 24933642));
+    assert_eq!(Day07::run(input), Ok((95437, 24933642)));
 }
@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 type Position = (isize, isize);
 
@@ -12,14 +15,14 @@ pub enum Direction {
     Right,
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            'U' => Direction::Up,
-            'D' => Direction::Down,
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => unreachable!(),
+impl Direction {
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
+        match raw {
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            _ => Err(src.error(raw, "U, D, L or R")),
         }
     }
 }
@@ -99,27 +102,33 @@ impl Rope {
 
 pub struct Day09;
 impl Day for Day09 {
+    const DAY: usize = 9;
+
     type Input = Vec<Direction>;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        Rope::count_tail_locations(2, &input)
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        Rope::count_tail_locations(2, input)
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        Rope::count_tail_locations(10, &input)
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        Rope::count_tail_locations(10, input)
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.lines()
-            .flat_map(|line| {
-                let (direction, distance) = line.split_once(' ').unwrap();
-                let distance = distance.parse().unwrap();
-                let direction = Direction::from(direction.chars().next().unwrap());
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        raw.lines().try_fold(Vec::new(), |mut directions, line| {
+            let (direction, distance) = src.split_once(line, " ")?;
+            let distance = src.number(distance)?;
+            let direction = Direction::parse(&src, direction)?;
+
+            directions.extend(vec![direction; distance]);
 
-                vec![direction; distance]
-            })
-            .collect()
+            Ok(directions)
+        })
     }
 }
 
@@ -133,7 +142,7 @@ R 4
 D 1
 L 5
 R 2";
-    assert_eq!(Day09::run(input), (13, 1));
+    assert_eq!(Day09::run(input), Ok((13, 1)));
 
     let input = "R 5
 U 8
@@ -143,5 +152,5 @@ R 17
 D 10
 L 25
 U 20";
-    assert_eq!(Day09::run(input), (88, 36));
+    assert_eq!(Day09::run(input), Ok((88, 36)));
 }
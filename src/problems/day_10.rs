@@ -1,4 +1,8 @@
-use crate::day::Day;
+use crate::{
+    day::Day,
+    ocr,
+    parse::{ParseError, Source},
+};
 
 #[derive(Clone)]
 pub enum Instruction {
@@ -98,6 +102,13 @@ mod cpu {
             pub fn print(&mut self, c: char) {
                 self.screen.push(c)
             }
+
+            pub fn pixels(&self) -> Vec<Vec<bool>> {
+                self.screen
+                    .chunks(40)
+                    .map(|line| line.iter().map(|&c| c == '#').collect())
+                    .collect()
+            }
         }
 
         impl Display for CrtScreen {
@@ -107,8 +118,9 @@ mod cpu {
                     "{}",
                     self.screen
                         .chunks(40)
-                        .flat_map(|line| [line, &['\n']].concat())
-                        .collect::<String>()
+                        .map(|line| line.iter().collect::<String>())
+                        .collect::<Vec<_>>()
+                        .join("\n")
                 )
             }
         }
@@ -132,43 +144,40 @@ mod cpu {
 
 pub struct Day10;
 impl Day for Day10 {
+    const DAY: usize = 10;
+
     type Input = Vec<Instruction>;
-    type Output = isize;
+    type Output1 = isize;
+    type Output2 = String;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         let mut cpu = cpu::Cpu::new();
-        cpu.run(&input);
+        cpu.run(input);
 
         cpu.signal_values.into_iter().sum()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
         let mut screen = cpu::crt::CrtScreen::new();
         let mut cpu = cpu::Cpu::new().with_peripheral(&mut screen);
-        cpu.run(&input);
-        println!("{}", screen);
+        cpu.run(input);
 
-        // TODO: Parse generated output, and test against known string
-        0
+        // Programs that don't draw letters, such as the example, are left as the raw screen
+        ocr::read(&screen.pixels()).unwrap_or_else(|| screen.to_string())
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         raw.lines()
-            .map(|line| {
-                if let Some((_, amount)) = line.split_once(' ') {
-                    Instruction::Add(amount.parse().unwrap())
-                } else {
-                    Instruction::Nop
-                }
+            .map(|line| match line.split_once(' ') {
+                Some(("addx", amount)) => Ok(Instruction::Add(src.number(amount)?)),
+                None if line == "noop" => Ok(Instruction::Nop),
+                _ => Err(src.error(line, "`addx <amount>` or `noop`")),
             })
             .collect()
     }
-
-    fn run(input: &str) -> (Self::Output, Self::Output) {
-        let input = Self::parse(input);
-
-        (Self::part_1(input.clone()), Self::part_2(input))
-    }
 }
 
 #[test]
@@ -319,5 +328,17 @@ addx -11
 noop
 noop
 noop";
-    assert_eq!(Day10::run(input), (13140, 0));
+    assert_eq!(
+        Day10::run(input),
+        Ok((
+            13140,
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+                .to_string()
+        ))
+    );
 }
@@ -1,20 +1,23 @@
 use std::collections::HashMap;
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
 const WORRY_REDUCE: usize = 3;
 
 type Worry = u64;
 type MonkeyNumber = usize;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Operation {
     Mult(usize),
     Add(usize),
     Square,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Monkey {
     pub operation: Operation,
     pub divisor: usize,
@@ -79,90 +82,95 @@ fn calculate_monkey_business(
 
 pub struct Day11;
 impl Day for Day11 {
+    const DAY: usize = 11;
+
     type Input = (Vec<Monkey>, HashMap<MonkeyNumber, Vec<Worry>>);
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
+
+    fn part_1((monkeys, items): &Self::Input) -> Self::Output1 {
+        let (mut monkeys, mut items) = (monkeys.clone(), items.clone());
 
-    fn part_1((mut monkeys, mut items): Self::Input) -> Self::Output {
         calculate_monkey_business(&mut monkeys, &mut items, 20, &|item| {
             item / WORRY_REDUCE as Worry
         })
     }
 
-    fn part_2((mut monkeys, mut items): Self::Input) -> Self::Output {
+    fn part_2((monkeys, items): &Self::Input) -> Self::Output2 {
+        let (mut monkeys, mut items) = (monkeys.clone(), items.clone());
+
         // Find common divisor
         let div: Worry = monkeys.iter().map(|m| m.divisor as u64).product();
 
         calculate_monkey_business(&mut monkeys, &mut items, 10000, &|item| item % div)
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         let mut items = HashMap::new();
+        let monkey_count = raw.split("\n\n").count();
 
         let monkeys = raw
             .split("\n\n")
             .enumerate()
             .map(|(i, raw_monkey)| {
-                let mut raw_monkey = raw_monkey.lines().skip(1);
+                let mut lines = raw_monkey.lines().skip(1);
+
+                // Find the next line, and strip the expected prefix from it
+                let mut field = |prefix: &str| {
+                    let line = src.require(lines.next(), raw_monkey, format!("{prefix:?}"))?;
+                    src.strip_prefix(line.trim_start(), prefix)
+                };
 
                 items.insert(
                     i,
-                    raw_monkey
-                        .next()
-                        .unwrap()
-                        .split_once(": ")
-                        .map(|(_, rhs)| rhs.split(", "))
-                        .unwrap()
-                        .map(|n| n.parse().unwrap())
-                        .collect(),
+                    field("Starting items: ")?
+                        .split(", ")
+                        .map(|n| src.number(n))
+                        .collect::<Result<_, _>>()?,
                 );
 
-                let operation = raw_monkey
-                    .next()
-                    .unwrap()
-                    .split_once(" = ")
-                    .map(|(_, rhs)| {
-                        let mut parts = rhs.split_whitespace().skip(1);
-                        let sign = parts.next().unwrap();
-                        let amount = parts.next().unwrap();
-
-                        if amount == "old" {
-                            Operation::Square
-                        } else {
-                            let amount = amount.parse().unwrap();
-
-                            match sign {
-                                "+" => Operation::Add(amount),
-                                "*" => Operation::Mult(amount),
-                                _ => unreachable!(),
-                            }
-                        }
-                    })
-                    .unwrap();
-
-                let divisor = raw_monkey
-                    .next()
-                    .unwrap()
-                    .split_whitespace()
-                    .last()
-                    .unwrap()
-                    .parse()
-                    .unwrap();
-
-                let mut next_monkeys = raw_monkey
-                    .take(2)
-                    .map(|raw| raw.split_whitespace().last().unwrap().parse().unwrap());
-
-                Monkey {
+                let operation = {
+                    let (sign, amount) = src.split_once(field("Operation: new = old ")?, " ")?;
+
+                    match (sign, amount) {
+                        ("*", "old") => Operation::Square,
+                        ("+", "old") => Operation::Mult(2),
+                        ("*", amount) => Operation::Mult(src.number(amount)?),
+                        ("+", amount) => Operation::Add(src.number(amount)?),
+                        _ => return Err(src.error(sign, "+ or *")),
+                    }
+                };
+
+                let raw_divisor = field("Test: divisible by ")?;
+                let divisor = src.number(raw_divisor)?;
+                if divisor == 0 {
+                    return Err(src.error(raw_divisor, "a divisor other than 0"));
+                }
+
+                let mut target = |prefix| {
+                    let raw_target = field(prefix)?;
+                    match src.number(raw_target)? {
+                        target if target < monkey_count => Ok(target),
+                        _ => Err(src.error(raw_target, "a monkey that exists")),
+                    }
+                };
+                let next_monkey_true = target("If true: throw to monkey ")?;
+                let next_monkey_false = target("If false: throw to monkey ")?;
+
+                Ok(Monkey {
                     inspected_count: 0,
                     operation,
                     divisor,
-                    next_monkey_true: next_monkeys.next().unwrap(),
-                    next_monkey_false: next_monkeys.next().unwrap(),
-                }
+                    next_monkey_true,
+                    next_monkey_false,
+                })
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        (monkeys, items)
+        Ok((monkeys, items))
     }
 }
 
@@ -196,5 +204,14 @@ Monkey 3:
     If true: throw to monkey 0
     If false: throw to monkey 1";
 
-    assert_eq!(Day11::run(input), (10605, 2713310158));
+    assert_eq!(Day11::run(input), Ok((10605, 2713310158)));
+
+    // Nothing is divisible by 0, and items can't go to monkeys that aren't there
+    let error = Day11::parse(&input.replace("by 23", "by 0"), &()).unwrap_err();
+    assert_eq!((error.line, error.column), (4, 22));
+    assert_eq!(error.expected, "a divisor other than 0");
+
+    let error = Day11::parse(&input.replacen("monkey 3", "monkey 4", 1), &()).unwrap_err();
+    assert_eq!((error.line, error.column), (6, 31));
+    assert_eq!(error.expected, "a monkey that exists");
 }
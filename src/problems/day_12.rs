@@ -1,113 +1,128 @@
-use std::collections::VecDeque;
+use std::{cmp::Ordering, fmt::Display};
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    grid::{Grid, Point},
+    parse::{ParseError, Source},
+    search::{self, Graph, Path},
+};
 
-type Position = (usize, usize);
-type Map = Vec<Vec<usize>>;
+type Map = Grid<usize>;
 
 const START_ELEVATION: usize = 0;
 const END_ELEVATION: usize = 25;
 
-fn find_shortest_path(points: &[Position], map: &Map, end: Position) -> usize {
-    let mut queue = VecDeque::new();
-    let mut visited = vec![vec![false; map[0].len()]; map.len()];
-
-    for p in points {
-        queue.push_back((p.to_owned(), 0));
+/// The squares one step from `pos` that are at most one higher, so can be climbed up to.
+fn climbable(map: &Map) -> impl Graph<Point> + '_ {
+    move |&pos: &Point| {
+        map.neighbours(pos)
+            .filter(move |&next| map[pos] + 1 >= map[next])
+            .map(|next| (next, 1))
+    }
+}
 
-        visited[p.1][p.0] = true;
+/// The squares one step from `pos` that could climb up to it, for searching down from the top.
+fn descendable(map: &Map) -> impl Graph<Point> + '_ {
+    move |&pos: &Point| {
+        map.neighbours(pos)
+            .filter(move |&next| map[next] + 1 >= map[pos])
+            .map(|next| (next, 1))
     }
+}
 
-    while let Some((pos, distance)) = queue.pop_front() {
-        if pos == end {
-            return distance;
+/// The way up to the best signal, if there is one.
+#[derive(Debug)]
+pub struct Route(pub Option<Path<Point>>);
+
+impl Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(path) => write!(f, "{}", path.cost),
+            None => write!(f, "no route"),
         }
+    }
+}
 
-        let current_elevation = map[pos.1][pos.0];
-
-        // Get neighbors
-        queue.extend(
-            [(0, 1), (1, 0), (0, -1), (-1, 0)]
-                .into_iter()
-                .filter_map(|(dx, dy): (isize, isize)| {
-                    if let (Some(x), Some(y)) =
-                        (pos.0.checked_add_signed(dx), pos.1.checked_add_signed(dy))
-                    {
-                        let next_pos = (x, y);
-                        if next_pos.1 < map.len()
-                            && next_pos.0 < map[0].len()
-                            && !visited[next_pos.1][next_pos.0]
-                        {
-                            let next_elevation = map[next_pos.1][next_pos.0];
-
-                            // Make sure height difference is valid
-                            if current_elevation + 1 >= next_elevation {
-                                visited[next_pos.1][next_pos.0] = true;
-                                return Some(next_pos);
-                            }
-                        }
-                    }
-                    None
-                })
-                .map(|pos| (pos, distance + 1)),
-        );
+/// Draws the heightmap with the route marked out in arrows, ending at an `E`, like the puzzle.
+#[allow(dead_code)]
+pub fn render(map: &Map, path: &Path<Point>) -> Grid<char> {
+    let mut drawing = map.map(|&elevation| (b'a' + elevation as u8) as char);
+
+    for step in path.nodes.windows(2) {
+        let (from, to) = (step[0], step[1]);
+
+        drawing[from] = match (to.x.cmp(&from.x), to.y.cmp(&from.y)) {
+            (Ordering::Greater, _) => '>',
+            (Ordering::Less, _) => '<',
+            (_, Ordering::Greater) => 'v',
+            _ => '^',
+        };
+    }
+
+    if let Some(&end) = path.nodes.last() {
+        drawing[end] = 'E';
     }
 
-    unreachable!();
+    drawing
 }
 
 pub struct Day12;
 impl Day for Day12 {
-    type Input = (Position, Position, Map);
-    type Output = usize;
-
-    fn part_1((start, end, map): Self::Input) -> Self::Output {
-        find_shortest_path(&[start], &map, end)
+    const DAY: usize = 12;
+
+    type Input = (Point, Point, Map);
+    type Output1 = Route;
+    type Output2 = Route;
+    type Params = ();
+
+    fn part_1(&(start, end, ref map): &Self::Input) -> Self::Output1 {
+        Route(search::a_star(
+            &climbable(map),
+            [start],
+            |&pos| pos == end,
+            |pos| pos.x.abs_diff(end.x) + pos.y.abs_diff(end.y),
+        ))
     }
 
-    fn part_2((_, end, map): Self::Input) -> Self::Output {
-        // Find all grid locations of elevation 0
-        find_shortest_path(
-            &map.iter()
-                .enumerate()
-                .flat_map(|(y, row)| {
-                    row.iter()
-                        .enumerate()
-                        .filter(|(_, &c)| c == START_ELEVATION)
-                        .map(move |(x, _)| (x, y))
-                })
-                .collect::<Vec<_>>(),
-            &map,
-            end,
-        )
+    fn part_2(&(_, end, ref map): &Self::Input) -> Self::Output2 {
+        // Head down from the end to whichever lowest square is closest
+        let path = search::bfs(&descendable(map), [end], |&pos| map[pos] == START_ELEVATION);
+
+        Route(path.map(|mut path| {
+            path.nodes.reverse();
+            path
+        }))
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-
-        let map = raw
-            .lines()
-            .enumerate()
-            .map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .map(|(x, c)| match c {
-                        'S' => {
-                            start = (x, y);
-                            START_ELEVATION
-                        }
-                        'E' => {
-                            end = (x, y);
-                            END_ELEVATION
-                        }
-                        _ => c as usize - 'a' as usize,
-                    })
-                    .collect()
-            })
-            .collect();
-
-        (start, end, map)
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        let mut start = None;
+        let mut end = None;
+
+        let map = Grid::parse(
+            &src,
+            raw,
+            "an elevation (a-z), `S` or `E`",
+            |p, c| match c {
+                'S' => {
+                    start = Some(p);
+                    Some(START_ELEVATION)
+                }
+                'E' => {
+                    end = Some(p);
+                    Some(END_ELEVATION)
+                }
+                'a'..='z' => Some(c as usize - 'a' as usize),
+                _ => None,
+            },
+        )?;
+
+        Ok((
+            src.require(start, raw, "a start position `S`")?,
+            src.require(end, raw, "an end position `E`")?,
+            map,
+        ))
     }
 }
 
@@ -119,5 +134,25 @@ accszExk
 acctuvwj
 abdefghi";
 
-    assert_eq!(Day12::run(input), (31, 29));
+    let steps = Day12::run(input).map(|(part_1, part_2)| (part_1.to_string(), part_2.to_string()));
+    assert_eq!(steps, Ok(("31".to_string(), "29".to_string())));
+
+    let (_, _, map) = Day12::parse(input, &()).unwrap();
+    let (part_1, part_2) = Day12::run(input).unwrap();
+    assert_eq!(
+        render(&map, &part_1.0.unwrap()).to_string(),
+        ">>vv<<<<
+abvvv<<^
+acvv>E^^
+acv>>>^^
+ab>>>>>^"
+    );
+    assert_eq!(part_2.0.unwrap().nodes.first(), Some(&Point::new(0, 4)));
+
+    // The summit can't be reached over the cliff
+    let input = "SbcdE";
+    assert_eq!(
+        Day12::run(input).map(|(part_1, _)| part_1.to_string()),
+        Ok("no route".to_string())
+    );
 }
@@ -1,14 +1,17 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt::Display, iter::Peekable, slice};
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct PacketList {
     list: Vec<Packet>,
     divider_packet: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Packet {
     Integer(usize),
     List(PacketList),
@@ -24,11 +27,31 @@ impl Packet {
         })
     }
 
-    pub fn as_list(&self) -> Self {
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            src,
+            raw,
+            tokens: Tokens { rest: raw }.peekable(),
+        };
+
+        match parser.tokens.next() {
+            Some((Token::Open, _)) => {}
+            Some((_, text)) => return Err(src.error(text, "a list")),
+            None => return Err(parser.end("a list")),
+        }
+        let packet = parser.list()?;
+
+        match parser.tokens.next() {
+            Some((_, text)) => Err(src.error(text, "the end of the packet")),
+            None => Ok(packet),
+        }
+    }
+
+    /// The items in the packet, where an integer is a list of just itself.
+    pub fn items(&self) -> &[Packet] {
         match self {
-            Packet::Integer(integer) => vec![Packet::Integer(*integer)].into(),
-            // TODO: not super happy with this clone
-            list => list.clone(),
+            Packet::Integer(_) => slice::from_ref(self),
+            Packet::List(list) => &list.list,
         }
     }
 }
@@ -42,55 +65,108 @@ impl From<Vec<Packet>> for Packet {
     }
 }
 
-impl From<&str> for Packet {
-    fn from(raw: &str) -> Self {
-        if raw.chars().all(|c| c.is_ascii_digit()) {
-            Self::Integer(raw.parse().unwrap())
-        } else {
-            let mut packet = Vec::new();
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(integer) => write!(f, "{integer}"),
+            Packet::List(PacketList { list, .. }) => {
+                write!(f, "[")?;
+                for (i, packet) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Integer,
+    Unexpected,
+}
 
-            let mut chars = raw.chars();
+/// Splits a packet into tokens, each along with its text.
+struct Tokens<'a> {
+    rest: &'a str,
+}
 
-            // Skip the first open bracket
-            chars.next();
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (Token, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.rest.chars().next()?;
+
+        let (token, len) = match c {
+            '[' => (Token::Open, 1),
+            ']' => (Token::Close, 1),
+            ',' => (Token::Comma, 1),
+            '0'..='9' => (
+                Token::Integer,
+                self.rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(self.rest.len()),
+            ),
+            c => (Token::Unexpected, c.len_utf8()),
+        };
+
+        let (text, rest) = self.rest.split_at(len);
+        self.rest = rest;
+
+        Some((token, text))
+    }
+}
 
-            while let Some(c) = chars.next() {
-                match c {
-                    '0'..='9' => {
-                        let mut n = vec![c];
-                        n.extend(chars.clone().take_while(|c| c.is_ascii_digit()).to_owned());
+/// Builds packets from tokens by recursive descent, reading each token once.
+struct Parser<'a, 'b> {
+    src: &'b Source<'a>,
+    raw: &'a str,
+    tokens: Peekable<Tokens<'a>>,
+}
 
-                        packet.push(Packet::Integer(
-                            n.iter().collect::<String>().parse().unwrap(),
-                        ));
-                    }
-                    '[' => {
-                        // Starting new packet part
-                        let mut part_raw = c.to_string();
-                        let mut depth = 0;
-
-                        loop {
-                            let next_c = chars.next().unwrap();
-                            part_raw.push(next_c);
-
-                            if next_c == '[' {
-                                depth += 1;
-                            } else if next_c == ']' {
-                                if depth == 0 {
-                                    // End of packet part
-                                    packet.push(part_raw.as_str().into());
-                                    break;
-                                } else {
-                                    depth -= 1;
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
+impl Parser<'_, '_> {
+    /// An error for having run out of tokens.
+    fn end(&self, expected: &str) -> ParseError {
+        self.src.error(&self.raw[self.raw.len()..], expected)
+    }
 
-            packet.into()
+    /// An integer or list, from the next token onwards.
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        match self.tokens.next() {
+            Some((Token::Integer, text)) => Ok(Packet::Integer(self.src.number(text)?)),
+            Some((Token::Open, _)) => self.list(),
+            Some((_, text)) => Err(self.src.error(text, "an integer or list")),
+            None => Err(self.end("an integer or list")),
+        }
+    }
+
+    /// The rest of a list, whose opening bracket has already been read.
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        let mut list = Vec::new();
+
+        if self
+            .tokens
+            .next_if(|&(token, _)| token == Token::Close)
+            .is_some()
+        {
+            return Ok(list.into());
+        }
+
+        loop {
+            list.push(self.packet()?);
+
+            match self.tokens.next() {
+                Some((Token::Comma, _)) => {}
+                Some((Token::Close, _)) => return Ok(list.into()),
+                Some((_, text)) => return Err(self.src.error(text, "`,` or `]`")),
+                None => return Err(self.end("`,` or `]`")),
+            }
         }
     }
 }
@@ -99,29 +175,8 @@ impl Ord for Packet {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
             (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
-            (Packet::List(ref left), Packet::List(ref right)) => {
-                // Compare items manually
-                let mut left = left.list.iter();
-                let mut right = right.list.iter();
-                loop {
-                    let l = left.next();
-                    let r = right.next();
-
-                    match (l, r) {
-                        (Some(l), Some(r)) => {
-                            // Compare items, and short circuit if out of order
-                            match l.cmp(r) {
-                                Ordering::Equal => continue,
-                                o => return o,
-                            }
-                        }
-                        (None, Some(_)) => return Ordering::Less,
-                        (Some(_), None) => return Ordering::Greater,
-                        (None, None) => return Ordering::Equal,
-                    }
-                }
-            }
-            (left, right) => left.as_list().cmp(&right.as_list()),
+            // Item by item, until one is out of order or a list runs out
+            (left, right) => left.items().iter().cmp(right.items()),
         }
     }
 }
@@ -140,23 +195,28 @@ impl PartialOrd for Packet {
 
 pub struct Day13;
 impl Day for Day13 {
+    const DAY: usize = 13;
+
     type Input = Vec<(Packet, Packet)>;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
+    fn part_1(input: &Self::Input) -> Self::Output1 {
         input
-            .into_iter()
+            .iter()
             .enumerate()
             .filter(|(_, group)| matches!(group.0.cmp(&group.1), Ordering::Less))
             .map(|(i, _)| i + 1)
             .sum()
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        let dividers = [Packet::divider_packet(2), Packet::divider_packet(6)];
         let mut ordered = input
-            .into_iter()
+            .iter()
             .flat_map(|(left, right)| [left, right])
-            .chain([Packet::divider_packet(2), Packet::divider_packet(6)])
+            .chain(&dividers)
             .collect::<Vec<_>>();
 
         ordered.sort_unstable();
@@ -177,12 +237,17 @@ impl Day for Day13 {
             .product()
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         raw.split("\n\n")
             .map(|raw_group| {
-                let mut groups = raw_group.lines().map(|packet| packet.into());
+                let mut groups = raw_group.lines().map(|packet| Packet::parse(&src, packet));
 
-                (groups.next().unwrap(), groups.next().unwrap())
+                Ok((
+                    src.require(groups.next(), raw_group, "a packet")??,
+                    src.require(groups.next(), raw_group, "a second packet")??,
+                ))
             })
             .collect()
     }
@@ -215,5 +280,106 @@ fn test() {
 [1,[2,[3,[4,[5,6,0]]]],8,9]
 ";
 
-    assert_eq!(Day13::run(input), (13, 140));
+    assert_eq!(Day13::run(input), Ok((13, 140)));
+}
+#[test]
+fn test_parse() {
+    let input = "[1,[2,[3,[4,[5,6,7]]]],8,9]\n[[10,[]],[[]],0,123]";
+    let src = Source::new(Day13::DAY, input);
+
+    for line in input.lines() {
+        assert_eq!(Packet::parse(&src, line).unwrap().to_string(), line);
+    }
+    assert_eq!(Packet::divider_packet(2).to_string(), "[[2]]");
+
+    // Packets equal integers wrapped in lists, so compare the structure rather than the order
+    fn same_structure(left: &Packet, right: &Packet) -> bool {
+        match (left, right) {
+            (Packet::Integer(left), Packet::Integer(right)) => left == right,
+            (Packet::List(left), Packet::List(right)) => {
+                left.list.len() == right.list.len()
+                    && left
+                        .list
+                        .iter()
+                        .zip(&right.list)
+                        .all(|(left, right)| same_structure(left, right))
+            }
+            _ => false,
+        }
+    }
+
+    let expected = [
+        Packet::from(vec![
+            Packet::Integer(1),
+            vec![
+                Packet::Integer(2),
+                vec![
+                    Packet::Integer(3),
+                    vec![
+                        Packet::Integer(4),
+                        vec![Packet::Integer(5), Packet::Integer(6), Packet::Integer(7)].into(),
+                    ]
+                    .into(),
+                ]
+                .into(),
+            ]
+            .into(),
+            Packet::Integer(8),
+            Packet::Integer(9),
+        ]),
+        Packet::from(vec![
+            vec![Packet::Integer(10), vec![].into()].into(),
+            vec![vec![].into()].into(),
+            Packet::Integer(0),
+            Packet::Integer(123),
+        ]),
+    ];
+    for (line, expected) in input.lines().zip(&expected) {
+        let packet = Packet::parse(&src, line).unwrap();
+        assert!(
+            same_structure(&packet, expected),
+            "{line} parsed as {packet:?}"
+        );
+    }
+    assert!(!same_structure(
+        &Packet::Integer(0),
+        &vec![Packet::Integer(0)].into()
+    ));
+
+    let error = |raw: &str| {
+        let error = Packet::parse(&Source::new(Day13::DAY, raw), raw).unwrap_err();
+        (error.column, error.expected)
+    };
+    assert_eq!(error("[1,,2]"), (4, "an integer or list".to_string()));
+    assert_eq!(error("[1,2"), (5, "`,` or `]`".to_string()));
+    assert_eq!(error("[1]]"), (4, "the end of the packet".to_string()));
+    assert_eq!(error("[[1] 2]"), (5, "`,` or `]`".to_string()));
+    assert_eq!(error("1"), (1, "a list".to_string()));
+    assert_eq!(error(""), (1, "a list".to_string()));
+}
+#[test]
+fn test_order() {
+    let src = Source::new(Day13::DAY, "");
+    let packet = |raw: &str| Packet::parse(&src, raw).unwrap();
+
+    assert_eq!(Packet::Integer(3).cmp(&packet("[[[3]]]")), Ordering::Equal);
+    assert_eq!(packet("[3]").cmp(&packet("[[3],0]")), Ordering::Less);
+    assert_eq!(packet("[[4,4],4]").cmp(&packet("[4,4]")), Ordering::Greater);
+
+    // Deeply nested packets, which used to be copied for every comparison against an integer
+    let mut packets = (0..2000)
+        .map(|i| {
+            let depth = i % 100;
+            packet(&format!(
+                "{}{}{}",
+                "[".repeat(depth + 1),
+                i % 7,
+                "]".repeat(depth + 1)
+            ))
+        })
+        .collect::<Vec<_>>();
+    packets.sort_unstable();
+
+    assert!(packets.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(packets[0].cmp(&packet("[0]")), Ordering::Equal);
 }
@@ -1,211 +1,203 @@
-use std::{collections::HashMap, fmt::Display, rc::Rc};
+use std::fmt::Display;
 
-use crate::day::Day;
+use crate::{
+    day::{param, Day, ParamError, Params},
+    grid::{Grid, Offset, Point},
+    parse::{ParseError, Source},
+};
 
-#[derive(Clone, Debug)]
-pub enum Block {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Air,
     Rock,
     Sand,
 }
 
-type Position = (usize, usize);
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tile::Air => ' ',
+                Tile::Rock => '█',
+                Tile::Sand => '●',
+            }
+        )
+    }
+}
 
-#[derive(Clone)]
-pub struct Map {
-    map: HashMap<Position, Block>,
-    sand_test: Option<Rc<dyn Fn(Position) -> bool>>,
-    floor: Option<usize>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Day14Params {
+    /// Where the sand pours in from.
+    pub origin: (usize, usize),
+}
 
-    y_bound: usize,
-    x_bound: (usize, usize),
+impl Default for Day14Params {
+    fn default() -> Self {
+        Self { origin: (500, 0) }
+    }
 }
 
-impl Map {
-    pub fn drop_sand(&mut self, start: Position) -> Option<Position> {
-        let mut next_pos = start;
-
-        loop {
-            // Test the condition to stop dropping sand
-            if let Some(ref sand_test) = self.sand_test {
-                if sand_test(next_pos) {
-                    return None;
-                }
+impl Params for Day14Params {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        const EXPECTED: &str = "a point such as 500,0";
+
+        match name {
+            "origin" => {
+                let (x, y) = value.split_once(',').ok_or_else(|| ParamError::Invalid {
+                    name: name.to_string(),
+                    expected: EXPECTED,
+                })?;
+                self.origin = (param(name, x, EXPECTED)?, param(name, y, EXPECTED)?);
             }
+            _ => return Err(ParamError::Unknown(name.to_string())),
+        }
 
-            if let Some(floor) = self.floor {
-                if next_pos.1 == floor {
-                    break;
-                }
-            }
+        Ok(())
+    }
+}
 
-            let (down, left, right) = {
-                let mut nodes = [0, -1, 1]
-                    .into_iter()
-                    .map(|dx| (next_pos.0.checked_add_signed(dx).unwrap(), next_pos.1 + 1))
-                    .map(|pos| (self.map.get(&pos), pos));
-
-                (
-                    nodes.next().unwrap(),
-                    nodes.next().unwrap(),
-                    nodes.next().unwrap(),
-                )
-            };
-
-            next_pos = match (down.0, left.0, right.0) {
-                (None, _, _) => down.1,
-                (Some(_), None, _) => left.1,
-                (Some(_), Some(_), None) => right.1,
-                (Some(_), Some(_), Some(_)) => {
-                    // Come to rest
-                    break;
-                }
+/// The order sand tries to fall in: straight down, then down and to the left, then the right.
+const FALLS: [Offset; 3] = [Offset::DOWN, Offset::new(-1, 1), Offset::new(1, 1)];
+
+/// The cave, cropped to the area sand can reach. The bottom row is the one just above the floor,
+/// beneath the lowest rock.
+#[derive(Clone)]
+pub struct Map {
+    grid: Grid<Tile>,
+    origin: Point,
+}
+
+impl Map {
+    /// Drops a unit of sand from the origin, returning where it comes to rest. Without a floor,
+    /// sand reaching the bottom row falls forever and `None` is returned, as it is once sand
+    /// has blocked the origin.
+    pub fn drop_sand(&mut self, floor: bool) -> Option<Point> {
+        let mut sand = self.origin;
+        if self.grid[sand] != Tile::Air {
+            return None;
+        }
+
+        while sand.y + 1 < self.grid.height() {
+            match FALLS
+                .into_iter()
+                .filter_map(|offset| self.grid.step(sand, offset))
+                .find(|&next| self.grid[next] == Tile::Air)
+            {
+                Some(next) => sand = next,
+                None => break,
             }
         }
 
-        // Insert sand position
-        self.map.insert(next_pos, Block::Sand);
+        if sand.y + 1 == self.grid.height() && !floor {
+            return None;
+        }
 
-        // Update bounds
-        self.y_bound = self.y_bound.max(next_pos.1);
-        self.x_bound.0 = self.x_bound.0.min(next_pos.0);
-        self.x_bound.1 = self.x_bound.1.max(next_pos.0);
+        self.grid[sand] = Tile::Sand;
 
-        Some(next_pos)
+        Some(sand)
     }
-}
 
-impl From<&str> for Map {
-    fn from(raw: &str) -> Self {
-        let (map, y_bound, x_bound) = raw
+    pub fn parse(src: &Source, raw: &str, origin: (usize, usize)) -> Result<Self, ParseError> {
+        let rocks = raw
             .lines()
-            .flat_map(|rock| {
-                rock.to_owned()
-                    .split(" -> ")
+            .map(|rock| {
+                rock.split(" -> ")
                     .map(|point| {
-                        let (x, y) = point.split_once(',').unwrap();
-                        (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap())
-                    })
-                    .collect::<Vec<_>>()[..]
-                    .windows(2)
-                    .flat_map(|points| {
-                        let start = points[0];
-                        let end = points[1];
-
-                        if start.0 == end.0 {
-                            let (low, high) = if start.1 > end.1 {
-                                (end.1, start.1)
-                            } else {
-                                (start.1, end.1)
-                            };
-                            (low..=high).map(|y| (start.0, y)).collect::<Vec<_>>()
-                        } else {
-                            let (low, high) = if start.0 > end.0 {
-                                (end.0, start.0)
-                            } else {
-                                (start.0, end.0)
-                            };
-                            (low..=high).map(|x| (x, start.1)).collect::<Vec<_>>()
-                        }
+                        let (x, y) = src.split_once(point, ",")?;
+                        let point = Point::new(src.number(x)?, src.number(y)?);
+
+                        Ok((point, x))
                     })
-                    // TODO: Don't like that this has to be allocated, but &str references are
-                    // causing problems
-                    .collect::<Vec<_>>()
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .fold(
-                (HashMap::new(), 0, None::<(usize, usize)>),
-                |(mut map, y_bound, x_bound), point: Position| {
-                    map.insert(point, Block::Rock);
-
-                    (
-                        map,
-                        y_bound.max(point.1),
-                        if let Some(x_bound) = x_bound {
-                            Some((x_bound.0.min(point.0), x_bound.1.max(point.0)))
-                        } else {
-                            Some(point)
-                        },
-                    )
-                },
-            );
-
-        Self {
-            map,
-            sand_test: None,
-            floor: None,
-
-            x_bound: x_bound.unwrap(),
-            y_bound,
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Rock paths can only be made of straight lines
+        for points in rocks.iter().flat_map(|rock| rock.windows(2)) {
+            let ((start, _), (end, raw_end)) = (points[0], points[1]);
+
+            if start.x != end.x && start.y != end.y {
+                return Err(src.error(
+                    raw_end,
+                    format!("a point in line with {},{}", start.x, start.y),
+                ));
+            }
+        }
+
+        let y_bound = src.require(
+            rocks.iter().flatten().map(|(point, _)| point.y).max(),
+            raw,
+            "a rock path",
+        )?;
+        if y_bound < origin.1 {
+            return Err(src.error(
+                &raw[raw.len()..],
+                format!(
+                    "a rock path below the sand origin at {},{}",
+                    origin.0, origin.1
+                ),
+            ));
+        }
+
+        // Sand spreads at most one column sideways for each row it falls
+        let height = y_bound + 2;
+        let (min_x, max_x) = rocks.iter().flatten().fold(
+            (origin.0.saturating_sub(height), origin.0 + height),
+            |(min_x, max_x), (point, _)| (min_x.min(point.x), max_x.max(point.x)),
+        );
+
+        let mut grid = Grid::new(max_x - min_x + 1, height, Tile::Air);
+        for points in rocks.iter().flat_map(|rock| rock.windows(2)) {
+            let (start, end) = (points[0].0, points[1].0);
+
+            for x in start.x.min(end.x)..=start.x.max(end.x) {
+                for y in start.y.min(end.y)..=start.y.max(end.y) {
+                    grid[Point::new(x - min_x, y)] = Tile::Rock;
+                }
+            }
         }
+
+        Ok(Self {
+            grid,
+            origin: Point::new(origin.0 - min_x, origin.1),
+        })
     }
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let output = self
-            .map
-            .iter()
-            .fold(
-                vec![vec![' '; self.x_bound.1 - self.x_bound.0 + 1]; self.y_bound + 1],
-                |mut output, (pos, c)| {
-                    output[pos.1][pos.0 - self.x_bound.0] = match c {
-                        Block::Rock => '█',
-                        Block::Sand => '●',
-                    };
-
-                    output
-                },
-            )
-            .into_iter()
-            .map(|row| row.into_iter().collect())
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        write!(f, "{output}")
+        write!(f, "{}", self.grid)
     }
 }
 
-const SAND_ORIGIN: (usize, usize) = (500, 0);
-
 pub struct Day14;
 impl Day for Day14 {
+    const DAY: usize = 14;
+
     type Input = Map;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = Day14Params;
 
-    fn part_1(mut map: Self::Input) -> Self::Output {
-        map.sand_test = Some(Rc::new(move |pos| pos.1 >= map.y_bound));
+    fn part_1(map: &Self::Input) -> Self::Output1 {
+        let mut map = map.clone();
 
-        let mut sand_counter = 0;
-        loop {
-            if map.drop_sand(SAND_ORIGIN).is_none() {
-                break;
-            } else {
-                sand_counter += 1;
-            }
-        }
-
-        sand_counter
+        std::iter::from_fn(|| map.drop_sand(false)).count()
     }
 
-    fn part_2(mut map: Self::Input) -> Self::Output {
-        map.floor = Some(map.y_bound + 1);
-
-        let mut sand_counter = 0;
-        loop {
-            let dropped = map.drop_sand(SAND_ORIGIN);
-            sand_counter += 1;
+    fn part_2(map: &Self::Input) -> Self::Output2 {
+        let mut map = map.clone();
 
-            if let Some(SAND_ORIGIN) = dropped {
-                break;
-            }
-        }
-
-        println!("{map}");
-
-        sand_counter
+        // Sand piles up until it blocks the origin
+        std::iter::from_fn(|| map.drop_sand(true)).count()
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.into()
+    fn parse(raw: &str, params: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        Map::parse(&src, raw, params.origin)
     }
 }
 
@@ -214,5 +206,12 @@ fn test() {
     let input = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
 
-    assert_eq!(Day14::run(input), (24, 93));
+    assert_eq!(Day14::run(input), Ok((24, 93)));
+
+    // Pouring in to the right of every rock, the sand all falls into the abyss
+    let params = Day14Params { origin: (504, 0) };
+    assert_eq!(
+        Day14::run_with(input, &params).map(|(part_1, _)| part_1),
+        Ok(0)
+    );
 }
@@ -1,10 +1,43 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{collections::HashSet, fmt::Display, ops::RangeInclusive};
 
-use crate::day::Day;
+use crate::{
+    day::{param, Day, ParamError, Params},
+    interval::IntervalSet,
+    parse::{ParseError, Source},
+};
 
 type Position = (isize, isize);
 
-const MULTIPLIER: usize = 4000000;
+const MULTIPLIER: isize = 4000000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Day15Params {
+    /// The row to count the squares that can't hold a beacon on.
+    pub row: isize,
+    /// The largest x and y coordinate the distress beacon could be at.
+    pub bound: isize,
+}
+
+impl Default for Day15Params {
+    fn default() -> Self {
+        Self {
+            row: 2000000,
+            bound: 4000000,
+        }
+    }
+}
+
+impl Params for Day15Params {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "row" => self.row = param(name, value, "a row number")?,
+            "bound" => self.bound = param(name, value, "a coordinate")?,
+            _ => return Err(ParamError::Unknown(name.to_string())),
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Sensor {
@@ -16,156 +49,142 @@ impl Sensor {
     pub fn known_distance(&self) -> usize {
         (self.position.0.abs_diff(self.beacon.0)) + (self.position.1.abs_diff(self.beacon.1))
     }
+
+    pub fn covers(&self, (x, y): Position) -> bool {
+        self.position.0.abs_diff(x) + self.position.1.abs_diff(y) <= self.known_distance()
+    }
+
+    /// The squares on row `y` that are no further from the sensor than its beacon.
+    pub fn coverage_on_row(&self, y: isize) -> Option<RangeInclusive<isize>> {
+        let x_spread = self
+            .known_distance()
+            .checked_sub(self.position.1.abs_diff(y))? as isize;
+
+        Some(self.position.0 - x_spread..=self.position.0 + x_spread)
+    }
+}
+
+/// Every square on row `y` that is covered by at least one sensor.
+pub fn coverage_on_row(sensors: &[Sensor], y: isize) -> IntervalSet {
+    sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage_on_row(y))
+        .collect()
+}
+
+/// Every square within the square `bounds` that no sensor covers, a row at a time.
+pub fn uncovered_points(
+    sensors: &[Sensor],
+    bounds: RangeInclusive<isize>,
+) -> impl Iterator<Item = Position> + '_ {
+    let row = IntervalSet::from_iter([bounds.clone()]);
+
+    bounds.flat_map(move |y| {
+        row.subtract(&coverage_on_row(sensors, y))
+            .iter()
+            .flatten()
+            .map(|x| (x, y))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Finds an uncovered square within the square `bounds` by checking only where the lines just
+/// outside each sensor's coverage cross. A single gap has to be boxed in by sensors on all
+/// sides, so sits on such a crossing, unless it is on the edge of the bounds.
+pub fn find_gap(sensors: &[Sensor], bounds: RangeInclusive<isize>) -> Option<Position> {
+    // Each sensor's coverage is a diamond, whose edges lie along x + y = a and x - y = b
+    let (mut rising, mut falling) = (HashSet::new(), HashSet::new());
+    for sensor in sensors {
+        let (x, y) = sensor.position;
+        let reach = sensor.known_distance() as isize + 1;
+
+        rising.extend([x + y - reach, x + y + reach]);
+        falling.extend([x - y - reach, x - y + reach]);
+    }
+
+    rising
+        .iter()
+        .flat_map(|&a| falling.iter().map(move |&b| (a, b)))
+        .filter(|(a, b)| (a + b) % 2 == 0)
+        .map(|(a, b)| ((a + b) / 2, (a - b) / 2))
+        .filter(|&(x, y)| bounds.contains(&x) && bounds.contains(&y))
+        .find(|&point| !sensors.iter().any(|sensor| sensor.covers(point)))
+}
+
+/// Where the distress beacon must be, if anywhere.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DistressBeacon(pub Option<Position>);
+
+impl Display for DistressBeacon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some((x, y)) => write!(f, "{}", x * MULTIPLIER + y),
+            None => write!(f, "no gap"),
+        }
+    }
 }
 
 pub struct Day15;
 impl Day for Day15 {
-    type Input = (usize, usize, Vec<Sensor>);
-    type Output = usize;
+    const DAY: usize = 15;
 
-    fn part_1((goal_row, _, sensors): Self::Input) -> Self::Output {
-        sensors
-            .iter()
-            .fold(HashSet::new(), |mut squares, sensor| {
-                let y_spread = sensor.position.1.abs_diff(goal_row as isize);
-                let d = sensor.known_distance();
+    type Input = (Day15Params, Vec<Sensor>);
+    type Output1 = usize;
+    type Output2 = DistressBeacon;
+    type Params = Day15Params;
 
-                if y_spread <= d {
-                    let x_spread = (d - y_spread) as isize;
+    fn part_1((params, sensors): &Self::Input) -> Self::Output1 {
+        let goal_row = params.row;
+        let coverage = coverage_on_row(sensors, goal_row);
 
-                    squares.extend(sensor.position.0 - x_spread..=sensor.position.0 + x_spread);
-                }
+        // Squares with a beacon in them can't not have a beacon
+        let beacons = sensors
+            .iter()
+            .filter(|sensor| sensor.beacon.1 == goal_row)
+            .map(|sensor| sensor.beacon.0)
+            .filter(|&x| coverage.contains(x))
+            .collect::<HashSet<_>>();
 
-                squares
-            })
-            .len()
-            - sensors
-                .iter()
-                .filter(|s| s.beacon.1 == goal_row as isize)
-                .map(|s| s.beacon.0)
-                .collect::<HashSet<_>>()
-                .len()
+        coverage.len() - beacons.len()
     }
 
-    fn part_2((_, search_max, sensors): Self::Input) -> Self::Output {
-        for search_row in 0isize..=search_max as isize {
-            let free_ranges = sensors.iter().try_fold(
-                vec![0..=search_max as isize],
-                |mut free_ranges, sensor| {
-                    // Create the range for the sensor on this row
-                    let sensor_max_range = sensor.known_distance();
-                    let y_spread = sensor.position.1.abs_diff(search_row);
-
-                    if y_spread <= sensor_max_range {
-                        // Sensor can reach this row
-                        let x_spread = (sensor_max_range - y_spread) as isize;
-
-                        let sensor_range =
-                            sensor.position.0 - x_spread..=sensor.position.0 + x_spread;
-
-                        // Insert sensor range into the row range
-                        free_ranges = free_ranges
-                            .into_iter()
-                            .flat_map(|range| {
-                                match (
-                                    range.contains(sensor_range.start()),
-                                    range.contains(sensor_range.end()),
-                                ) {
-                                    (true, false) => {
-                                        vec![*range.start()..=*sensor_range.start() - 1]
-                                    }
-                                    (false, true) => vec![*sensor_range.end() + 1..=*range.end()],
-                                    (true, true) => {
-                                        // Subdivide the range
-                                        vec![
-                                            *range.start()..=*sensor_range.start() - 1,
-                                            *sensor_range.end() + 1..=*range.end(),
-                                        ]
-                                    }
-                                    (false, false) => {
-                                        // Either completely overlapping, or completely not
-                                        match (
-                                            sensor_range.start().cmp(range.start()),
-                                            sensor_range.end().cmp(range.end()),
-                                        ) {
-                                            (
-                                                Ordering::Less | Ordering::Equal,
-                                                Ordering::Greater | Ordering::Equal,
-                                            ) => vec![],
-                                            _ => vec![range],
-                                        }
-                                    }
-                                }
-                            })
-                            .collect();
-
-                        if free_ranges.is_empty() {
-                            return None;
-                        }
-                    }
-
-                    Some(free_ranges)
-                },
-            );
-
-            if let Some(free_ranges) = free_ranges {
-                match free_ranges.len() {
-                    0 => (),
-                    1 => {
-                        let x = free_ranges.into_iter().next().unwrap();
-
-                        return (*x.start() as usize * MULTIPLIER) + search_row as usize;
-                    }
-                    _ => {
-                        println!("Found row with {} available spaces", free_ranges.len());
-                    }
-                }
-            }
-        }
+    fn part_2((params, sensors): &Self::Input) -> Self::Output2 {
+        let bounds = 0..=params.bound;
 
-        0
+        DistressBeacon(
+            find_gap(sensors, bounds.clone()).or_else(|| uncovered_points(sensors, bounds).next()),
+        )
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        let mut raw = raw.lines();
-
-        (
-            raw.next().unwrap().parse().unwrap(),
-            raw.next().unwrap().parse().unwrap(),
-            raw.map(|raw| {
-                let (sensor, beacon) = raw.split_once(':').unwrap();
-                let (position, beacon) = {
-                    let mut iter = [sensor, beacon].into_iter().map(|section| {
-                        let mut parts = section.split(' ').rev().take(2).map(|part| {
-                            part.split_once('=')
-                                .unwrap()
-                                .1
-                                .replace(',', "")
-                                .parse()
-                                .unwrap()
-                        });
-
-                        // Reversed
-                        let y = parts.next().unwrap();
-                        let x = parts.next().unwrap();
-
-                        (x, y)
-                    });
-                    (iter.next().unwrap(), iter.next().unwrap())
+    fn parse(raw: &str, params: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        let sensors = raw
+            .lines()
+            .map(|line| {
+                let parse_position = |section| {
+                    let (_, coordinates) = src.split_once(section, "at x=")?;
+                    let (x, y) = src.split_once(coordinates, ", y=")?;
+
+                    Ok((src.number(x)?, src.number(y)?))
                 };
 
-                Sensor { position, beacon }
+                let (sensor, beacon) = src.split_once(line, ":")?;
+
+                Ok(Sensor {
+                    position: parse_position(sensor)?,
+                    beacon: parse_position(beacon)?,
+                })
             })
-            .collect(),
-        )
+            .collect::<Result<_, _>>()?;
+
+        Ok((params.clone(), sensors))
     }
 }
 
 #[test]
 fn test() {
-    let input = "10
-20
-Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+    let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
 Sensor at x=13, y=2: closest beacon is at x=15, y=3
 Sensor at x=12, y=14: closest beacon is at x=10, y=16
@@ -180,5 +199,20 @@ Sensor at x=16, y=7: closest beacon is at x=15, y=3
 Sensor at x=14, y=3: closest beacon is at x=15, y=3
 Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
-    assert_eq!(Day15::run(input), (26, 56000011));
+    let params = Day15Params { row: 10, bound: 20 };
+    assert_eq!(
+        Day15::run_with(input, &params),
+        Ok((26, DistressBeacon(Some((14, 11)))))
+    );
+
+    let (_, sensors) = Day15::parse(input, &params).unwrap();
+    assert_eq!(
+        coverage_on_row(&sensors, 11).iter().collect::<Vec<_>>(),
+        vec![-3..=13, 15..=25]
+    );
+    assert_eq!(
+        uncovered_points(&sensors, 0..=20).collect::<Vec<_>>(),
+        vec![(14, 11)]
+    );
+    assert_eq!(DistressBeacon(Some((14, 11))).to_string(), "56000011");
 }
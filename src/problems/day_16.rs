@@ -1,285 +1,227 @@
 use std::{
-    cell::RefCell,
-    collections::{HashMap, HashSet, VecDeque},
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
 };
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+    search,
+};
 
 pub struct Day16;
 
 type Identifier = usize;
 type OpenedHash = u64;
 
+const START: &str = "AA";
+
 #[derive(Clone, Debug)]
 pub struct Valve {
-    identifier: String,
     flow_rate: usize,
     connections: HashSet<Identifier>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct CaveSystem {
     valves: HashMap<Identifier, Valve>,
-    distance_cache: RefCell<HashMap<(Identifier, Identifier), usize>>,
-    flow_cache: RefCell<HashMap<(Identifier, usize, OpenedHash), usize>>,
-    flow_2_cache: RefCell<HashMap<(Identifier, usize, Identifier, usize, OpenedHash), usize>>,
+    start: Identifier,
+}
+
+/// The valves worth opening, and the time taken to travel between each of them, if there's a
+/// way there at all. The starting valve comes last, after the valves that can be opened.
+struct Network {
+    flow_rates: Vec<usize>,
+    distances: Vec<Vec<Option<usize>>>,
 }
 
 impl CaveSystem {
-    fn new(valves: HashMap<Identifier, Valve>) -> Self {
-        Self {
-            valves,
-            distance_cache: RefCell::new(HashMap::new()),
-            flow_cache: RefCell::new(HashMap::new()),
-            flow_2_cache: RefCell::new(HashMap::new()),
-        }
+    fn new(valves: HashMap<Identifier, Valve>, start: Identifier) -> Self {
+        Self { valves, start }
     }
 
-    fn distance(&self, x: Identifier, y: Identifier) -> usize {
-        let d = self
-            .distance_cache
-            .borrow()
-            .get(&{
-                if x < y {
-                    (x, y)
-                } else {
-                    (y, x)
-                }
+    /// Collapses the tunnels down to the valves with a flow rate, since the rest are only ever
+    /// passed through.
+    fn network(&self) -> Network {
+        let mut useful = self
+            .valves
+            .iter()
+            .filter(|(_, valve)| valve.flow_rate > 0)
+            .map(|(&identifier, _)| identifier)
+            .collect::<Vec<_>>();
+        useful.sort_unstable();
+
+        let flow_rates = useful
+            .iter()
+            .map(|identifier| self.valves[identifier].flow_rate)
+            .collect();
+
+        useful.push(self.start);
+        let tunnels = |valve: &Identifier| {
+            self.valves[valve]
+                .connections
+                .iter()
+                .map(|&connection| (connection, 1))
+        };
+        let distances = useful
+            .iter()
+            .map(|&x| {
+                let reached = search::reachable(&tunnels, [x]);
+                useful.iter().map(|y| reached.get(y).copied()).collect()
             })
-            .map(|d| d.to_owned());
-
-        d.unwrap_or_else(|| {
-            let mut visited = HashSet::new();
-            let mut queue = VecDeque::new();
-            queue.push_back((0, x));
-
-            while let Some((d, valve_identifier)) = queue.pop_front() {
-                if valve_identifier == y {
-                    // Insert into cache
-                    self.distance_cache.borrow_mut().insert((x, y), d);
-                    return d;
-                }
+            .collect();
 
-                visited.insert(valve_identifier);
-
-                let valve = self.valves.get(&valve_identifier).unwrap();
-                queue.extend(
-                    valve
-                        .connections
-                        .iter()
-                        .filter(|c| !visited.contains(*c))
-                        .cloned()
-                        .map(|c| (d + 1, c)),
-                );
-            }
-            unreachable!("Should be a continuous graph");
-        })
+        Network {
+            flow_rates,
+            distances,
+        }
     }
 
-    fn flow(&self, x: Identifier, t: usize, opened: OpenedHash) -> usize {
-        let flow = self
-            .flow_cache
-            .borrow()
-            .get(&(x, t, opened))
-            .map(|f| f.to_owned());
-
-        flow.unwrap_or_else(|| {
-            let valve = self.valves.get(&x).unwrap();
-
-            let flow = usize::max(
-                if (opened & (1 << x)) == 0 && valve.flow_rate > 0 {
-                    // Flow on
-                    let valve_flow = valve.flow_rate * (t - 1);
-                    let opened = opened | (1 << x);
-
-                    valve_flow + self.flow(x, t - 1, opened)
-                } else {
-                    0
-                },
-                {
-                    // Flow off
-                    valve
-                        .connections
-                        .iter()
-                        .filter_map(|&y| {
-                            let d = self.distance(x, y);
-
-                            if t > d {
-                                Some(self.flow(y, t - d, opened))
-                            } else {
-                                None
-                            }
-                        })
-                        .max()
-                        .unwrap_or(0)
-                },
-            );
+    /// Finds the most pressure that can be released within `time` for every set of valves that
+    /// can be opened in that time.
+    fn best_pressures(&self, time: usize) -> HashMap<OpenedHash, usize> {
+        let network = self.network();
+        let mut best = HashMap::new();
 
-            self.flow_cache.borrow_mut().insert((x, t, opened), flow);
+        network.explore(network.flow_rates.len(), time, 0, 0, &mut best);
 
-            flow
-        })
+        best
     }
+}
 
-    fn flow_2(
+impl Network {
+    fn explore(
         &self,
-        x1: Identifier,
-        t1: usize,
-        x2: Identifier,
-        t2: usize,
+        position: usize,
+        time: usize,
         opened: OpenedHash,
-    ) -> usize {
-        let (x1, t1, x2, t2) = if x1 < x2 {
-            (x1, t1, x2, t2)
-        } else {
-            (x2, t2, x1, t1)
-        };
+        pressure: usize,
+        best: &mut HashMap<OpenedHash, usize>,
+    ) {
+        let entry = best.entry(opened).or_default();
+        *entry = (*entry).max(pressure);
+
+        for (next, flow_rate) in self.flow_rates.iter().enumerate() {
+            if opened & (1 << next) != 0 {
+                continue;
+            }
 
-        let flow = self
-            .flow_2_cache
-            .borrow()
-            .get(&(x1, t1, x2, t2, opened))
-            .map(|f| f.to_owned());
-
-        flow.unwrap_or_else(|| {
-            let valve_1 = self.valves.get(&x1).unwrap();
-            let valve_2 = self.valves.get(&x2).unwrap();
-
-            let can_open =
-                |v| (opened & (1u64 << v) == 0) && self.valves.get(&v).unwrap().flow_rate > 0;
-            let open = |v, opened| opened | (1u64 << v);
-            let flow_rate = |v: &Valve, t| v.flow_rate * (t - 1);
-
-            let flow = [
-                if x1 != x2 && can_open(x1) && can_open(x2) {
-                    // open/open
-                    let opened = open(x1, open(x2, opened));
-
-                    flow_rate(valve_1, t1)
-                        + flow_rate(valve_2, t2)
-                        + self.flow_2(x1, t1 - 1, x2, t2 - 1, opened)
-                } else {
-                    0
-                },
-                if can_open(x1) {
-                    // open/step
-                    let opened = open(x1, opened);
-
-                    flow_rate(valve_1, t1)
-                        + valve_2
-                            .connections
-                            .iter()
-                            .filter_map(|&c| {
-                                let d = self.distance(x2, c);
-
-                                if t2 > d {
-                                    Some(self.flow_2(x1, t1 - 1, c, t2 - d, opened))
-                                } else {
-                                    None
-                                }
-                            })
-                            .max()
-                            .unwrap_or(0)
-                } else {
-                    0
-                },
-                if can_open(x2) {
-                    // step/open
-                    let opened = open(x2, opened);
-
-                    flow_rate(valve_2, t2)
-                        + valve_1
-                            .connections
-                            .iter()
-                            .filter_map(|&c| {
-                                let d = self.distance(x1, c);
-
-                                if t1 > d {
-                                    Some(self.flow_2(c, t1 - d, x2, t2 - 1, opened))
-                                } else {
-                                    None
-                                }
-                            })
-                            .max()
-                            .unwrap_or(0)
-                } else {
-                    0
-                },
-                {
-                    // step/step
-
-                    // Generate every possible permutation of steps
-                    valve_1
-                        .connections
-                        .iter()
-                        .filter_map(|&c1| {
-                            let d1 = self.distance(x1, c1);
-
-                            if t1 > d1 {
-                                Some(valve_2.connections.iter().filter_map(move |&c2| {
-                                    let d2 = self.distance(x2, c2);
-
-                                    if t2 > d2 {
-                                        Some(self.flow_2(c1, t1 - d1, c2, t2 - d2, opened))
-                                    } else {
-                                        None
-                                    }
-                                }))
-                            } else {
-                                None
-                            }
-                        })
-                        .flatten()
-                        .max()
-                        .unwrap_or(0)
-                },
-            ]
-            .into_iter()
-            .max()
-            .unwrap();
+            let Some(distance) = self.distances[position][next] else {
+                continue;
+            };
 
-            self.flow_2_cache
-                .borrow_mut()
-                .insert((x1, t1, x2, t2, opened), flow);
+            // Travel to the valve, then spend a minute opening it
+            let cost = distance + 1;
+            if cost >= time {
+                continue;
+            }
 
-            flow
-        })
+            let time = time - cost;
+            self.explore(
+                next,
+                time,
+                opened | (1 << next),
+                pressure + flow_rate * time,
+                best,
+            );
+        }
     }
 }
 
 impl Day for Day16 {
+    const DAY: usize = 16;
+
     type Input = CaveSystem;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        return 0;
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input.best_pressures(30).into_values().max().unwrap_or(0)
+    }
 
-        let start = input
-            .valves
-            .iter()
-            .find(|(_, v)| v.identifier == "AA")
-            .unwrap()
-            .0
-            .to_owned();
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        let mut best = input.best_pressures(26).into_iter().collect::<Vec<_>>();
+        best.sort_unstable_by_key(|&(_, pressure)| Reverse(pressure));
 
-        input.flow(start, 30, 0)
-    }
+        // You and the elephant each open a separate set of valves
+        let mut most = 0;
+        for (i, &(mine, my_pressure)) in best.iter().enumerate() {
+            if my_pressure * 2 <= most {
+                break;
+            }
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        return 0;
+            for &(theirs, their_pressure) in &best[i + 1..] {
+                if my_pressure + their_pressure <= most {
+                    break;
+                }
 
-        let start = input
-            .valves
-            .iter()
-            .find(|(_, v)| v.identifier == "AA")
-            .unwrap()
-            .0
-            .to_owned();
+                if mine & theirs == 0 {
+                    most = my_pressure + their_pressure;
+                }
+            }
+        }
 
-        input.flow_2(start, 26, start, 26, 0)
+        most
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        let valves = raw
+            .lines()
+            .map(|line| {
+                let line = src.strip_prefix(line, "Valve ")?;
+                let (identifier, line) = src.split_once(line, " has flow rate=")?;
+                let (flow_rate, tunnels) = src.split_once(line, "; ")?;
+                let flow_rate: usize = src.number(flow_rate)?;
+
+                let tunnels = tunnels
+                    .strip_prefix("tunnels lead to valves ")
+                    .or_else(|| tunnels.strip_prefix("tunnel leads to valve "))
+                    .ok_or_else(|| src.error(tunnels, "a list of tunnels"))?;
+
+                Ok((
+                    identifier,
+                    flow_rate,
+                    tunnels.split(", ").collect::<Vec<_>>(),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let start = src.require(
+            valves
+                .iter()
+                .find(|(identifier, _, _)| *identifier == START),
+            raw,
+            format!("a valve named {START}"),
+        )?;
+
+        // Every valve worth opening needs a bit in the opened hash
+        if let Some((identifier, _, _)) = valves
+            .iter()
+            .filter(|(_, flow_rate, _)| *flow_rate > 0)
+            .nth(OpenedHash::BITS as usize)
+        {
+            return Err(src.error(
+                identifier,
+                format!("at most {} valves with a flow rate", OpenedHash::BITS),
+            ));
+        }
+
+        // Make sure every tunnel leads to a known valve
+        let known = valves
+            .iter()
+            .map(|(identifier, _, _)| *identifier)
+            .collect::<HashSet<_>>();
+        if let Some(unknown) = valves
+            .iter()
+            .flat_map(|(_, _, tunnels)| tunnels)
+            .find(|tunnel| !known.contains(*tunnel))
+        {
+            return Err(src.error(unknown, "a valve that exists"));
+        }
+
         let mut identifier_map = HashMap::new();
         let mut next_identifier: usize = 0;
 
@@ -294,38 +236,21 @@ impl Day for Day16 {
                 .to_owned()
         };
 
-        CaveSystem::new(
-            raw.lines()
-                .map(|line| {
-                    let mut line = line.split_whitespace();
-                    let identifier_str = line.nth(1).unwrap();
-                    let identifier = get_identifier(identifier_str);
-                    let flow_rate = line
-                        .nth(2)
-                        .unwrap()
-                        .trim_end_matches(';')
-                        .split('=')
-                        .nth(1)
-                        .unwrap()
-                        .parse()
-                        .unwrap();
-
-                    let connections = line
-                        .skip(4)
-                        .map(|connection| get_identifier(connection.trim_end_matches(',')))
-                        .collect();
-
-                    (
-                        identifier,
-                        Valve {
-                            identifier: identifier_str.to_string(),
-                            flow_rate,
-                            connections,
-                        },
-                    )
-                })
-                .collect(),
-        )
+        let start = get_identifier(start.0);
+        let valves = valves
+            .into_iter()
+            .map(|(identifier, flow_rate, tunnels)| {
+                (
+                    get_identifier(identifier),
+                    Valve {
+                        flow_rate,
+                        connections: tunnels.into_iter().map(&mut get_identifier).collect(),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(CaveSystem::new(valves, start))
     }
 }
 
@@ -342,5 +267,12 @@ Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
 
-    assert_eq!(Day16::run(input), (1651, 1707));
+    assert_eq!(Day16::run(input), Ok((1651, 1707)));
+
+    // CC can never be reached, so is never opened
+    let input = "Valve AA has flow rate=0; tunnel leads to valve BB
+Valve BB has flow rate=5; tunnel leads to valve AA
+Valve CC has flow rate=7; tunnel leads to valve CC";
+
+    assert_eq!(Day16::run(input), Ok((140, 120)));
 }
@@ -1,14 +1,14 @@
-use std::{collections::HashSet, ops::Deref};
-use tqdm::tqdm;
+use std::collections::HashMap;
 
-use crate::day::Day;
+use crate::{
+    day::{param, Day, ParamError, Params},
+    grid::{Grid, Offset, Point},
+    parse::{ParseError, Source},
+};
 
-const NUM_ROCKS_1: usize = 2022;
-const NUM_ROCKS_2: usize = 1000000000000;
 const WIDTH: usize = 7;
 const Y_BUFFER: usize = 3;
 const STARTING_X_OFFSET: usize = 2;
-const ROCK_SIZE: usize = 4;
 const ROCKS: &str = "####
 
 .#.
@@ -33,302 +33,211 @@ pub enum Direction {
     Left,
     Right,
 }
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            '>' => Direction::Right,
-            '<' => Direction::Left,
-            _ => {
-                unreachable!()
-            }
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct Rock {
-    points: [[bool; ROCK_SIZE]; ROCK_SIZE],
-    height: usize,
-    width: usize,
+    shape: Grid<bool>,
 }
 
 impl Rock {
     pub fn new(raw: &str) -> Self {
-        let mut width = 0;
-        let mut height = 0;
-
-        let points = raw
-            .lines()
-            .rev()
-            .chain((raw.lines().count()..ROCK_SIZE).map(|_| ""))
-            .enumerate()
-            .map(|(y, row)| {
-                row.chars()
-                    .chain((row.chars().count()..ROCK_SIZE).map(|_| '.'))
-                    .enumerate()
-                    .map(|(x, c)| {
-                        if c == '#' {
-                            width = width.max(x + 1);
-                            height = height.max(y + 1);
-
-                            true
-                        } else {
-                            false
-                        }
-                    })
-                    .collect::<Vec<bool>>()
-                    .try_into()
-                    .unwrap()
-            })
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+        let src = Source::new(Day17::DAY, raw);
+        let shape = Grid::parse(&src, raw, "`#` or `.`", |_, c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .expect("rock shapes are well formed");
 
-        Self {
-            points,
-            width,
-            height,
-        }
+        Self { shape }
     }
-}
 
-impl Deref for Rock {
-    type Target = [[bool; ROCK_SIZE]; ROCK_SIZE];
+    /// The cells the rock fills, counting up from its bottom left corner.
+    fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+        let height = self.shape.height();
 
-    fn deref(&self) -> &Self::Target {
-        &self.points
+        self.shape
+            .iter()
+            .filter(|&(_, &filled)| filled)
+            .map(move |(p, _)| Point::new(p.x, height - 1 - p.y))
     }
 }
 
-fn world_to_hashset(world: &[Row]) -> HashSet<(usize, usize)> {
-    world
-        .iter()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.as_parts()
-                .into_iter()
-                .enumerate()
-                .filter_map(move |(x, c)| if c { Some((x, y)) } else { None })
-        })
-        .collect()
-}
+/// How far below the top of the tower the highest rock in each column is.
+type Skyline = [usize; WIDTH];
 
-fn print_tower(world: &[Row]) {
-    world.iter().enumerate().rev().for_each(|(y, row)| {
-        println!(
-            "{y:>2} | {}",
-            row.as_parts()
-                .into_iter()
-                .map(|c| if c { "#" } else { " " })
-                .collect::<Vec<_>>()
-                .join(" ")
-        );
-    });
-    println!("   -------------------");
-    println!(
-        "     {}",
-        (0..WIDTH)
-            .map(|c| c.to_string())
-            .collect::<Vec<_>>()
-            .join(" ")
-    );
-}
+struct Chamber<'a> {
+    rocks: &'a [Rock],
+    jets: &'a [Direction],
+    next_rock: usize,
+    next_jet: usize,
 
-#[derive(Hash, PartialEq, Eq)]
-struct TowerBitMask(u32);
-impl From<&Vec<Vec<bool>>> for TowerBitMask {
-    fn from(tower: &Vec<Vec<bool>>) -> Self {
-        Self(
-            tower
-                .iter()
-                .flatten()
-                .fold(0, |mask, &b| (mask << 1) | (b as u32)),
-        )
-    }
+    /// Settled rocks from the floor up. There are never any empty rows on top.
+    tower: Grid<bool>,
 }
 
-#[derive(Clone)]
-pub struct Row(u8);
-impl Row {
-    pub fn new() -> Self {
-        Row(0)
+impl<'a> Chamber<'a> {
+    pub fn new(rocks: &'a [Rock], jets: &'a [Direction]) -> Self {
+        Self {
+            rocks,
+            jets,
+            next_rock: 0,
+            next_jet: 0,
+            tower: Grid::new(WIDTH, 0, false),
+        }
     }
 
-    pub fn set(&mut self, i: usize) {
-        self.0 |= 1 << i as u8;
+    pub fn height(&self) -> usize {
+        self.tower.height()
     }
 
-    pub fn overlap(&self, points: &[usize]) -> bool {
-        points.iter().any(|&i| (self.0 >> i as u8) & 1 == 1)
+    fn collides(&self, rock: &Rock, x: usize, y: usize) -> bool {
+        rock.cells()
+            .any(|p| self.tower.get(Point::new(x + p.x, y + p.y)) == Some(&true))
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.0 == 0
-    }
+    pub fn drop_rock(&mut self) {
+        let rock = &self.rocks[self.next_rock];
+        self.next_rock = (self.next_rock + 1) % self.rocks.len();
 
-    pub fn as_parts(&self) -> [bool; WIDTH] {
-        (0..WIDTH).into_iter().fold([false; WIDTH], |mut arr, i| {
-            arr[i] = (self.0 >> i) & 1 == 1;
+        let (mut x, mut y) = (STARTING_X_OFFSET, self.height() + Y_BUFFER);
 
-            arr
-        })
-    }
-}
+        loop {
+            let jet = &self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+            let pushed_x = match jet {
+                Direction::Left => x.checked_sub(1),
+                Direction::Right => Some(x + 1).filter(|x| x + rock.shape.width() <= WIDTH),
+            };
+            if let Some(pushed_x) = pushed_x.filter(|&pushed_x| !self.collides(rock, pushed_x, y)) {
+                x = pushed_x;
+            }
 
-fn simulate(rocks: Vec<Rock>, jets: Vec<Direction>, num_rocks: usize) -> usize {
-    let largest_rock = rocks.iter().map(|r| r.height.max(r.width)).max().unwrap();
-    let mut rocks = rocks.into_iter().cycle();
-    let mut jets = jets.into_iter().cycle();
+            if y == 0 || self.collides(rock, x, y - 1) {
+                break;
+            }
 
-    let mut tower: Vec<Row> = vec![Row::new(); Y_BUFFER + ROCK_SIZE];
+            y -= 1;
+        }
 
-    for _ in tqdm(0..num_rocks) {
-        // Create next rock
-        let rock = rocks.next().unwrap();
+        for p in rock.cells() {
+            while y + p.y >= self.tower.height() {
+                self.tower.push_row([false; WIDTH]);
+            }
 
-        // Clear until top 3 rows are free
-        while !tower[tower.len() - Y_BUFFER.max(largest_rock)].is_empty() {
-            // tower.pop_front();
-            tower.push(Row::new());
+            self.tower[Point::new(x + p.x, y + p.y)] = true;
         }
+    }
 
-        // Find the correct place to drop from
-        let tower_top = tower
-            .iter()
-            .enumerate()
-            .rev() // start with top of tower
-            .skip(Y_BUFFER.max(largest_rock))
-            .find(|(_, row)| !row.is_empty())
-            .map(|(y, _)| y + 1)
-            .unwrap_or(0);
-
-        let mut position = (
-            STARTING_X_OFFSET
-                .saturating_add_signed(
-                    (0..Y_BUFFER)
-                        .map(|_| jets.next().unwrap())
-                        .map(|j| match j {
-                            Direction::Left => -1,
-                            Direction::Right => 1,
-                        })
-                        .sum(),
-                )
-                .min(WIDTH - rock.width),
-            tower_top,
-        );
+    pub fn skyline(&self) -> Skyline {
+        let mut skyline = [self.height(); WIDTH];
 
-        loop {
-            let jet = jets.next().unwrap();
-
-            let horizontal_collision = match jet {
-                Direction::Left => {
-                    if position.0 > 0 {
-                        Some(-1)
-                    } else {
-                        None
-                    }
-                }
-                Direction::Right => {
-                    if position.0 + rock.width < WIDTH {
-                        Some(1)
-                    } else {
-                        None
-                    }
-                }
-            }
-            .map(|dx: isize| {
-                // TODO: Check with wall
-                let rock_x = position.0.saturating_add_signed(dx).min(7);
-
-                // Check if new direction will collide with another rock
-                rock.iter().enumerate().any(|(y, row)| {
-                    let world_y = position.1 + y;
-
-                    if world_y < tower.len() {
-                        let row = row
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, &c)| c)
-                            .map(|(x, _)| x + rock_x)
-                            .collect::<Vec<_>>();
-
-                        tower[position.1 + y].overlap(&row)
-                    } else {
-                        // Row is above world, so won't collide
-                        false
-                    }
-                })
-            })
-            .unwrap_or(true);
-
-            if !horizontal_collision {
-                // Can move
-                position.0 = match jet {
-                    Direction::Left => position.0 - 1,
-                    Direction::Right => position.0 + 1,
-                };
+        for (x, depth) in skyline.iter_mut().enumerate() {
+            // Up the grid is down the tower, towards the floor
+            let top = Point::new(x, self.height());
+            if let Some(found) = self.tower.ray(top, Offset::UP).position(|p| self.tower[p]) {
+                *depth = found;
             }
+        }
 
-            // Check for collision below
-            let vertical_collision = position.1 == 0
-                || rock.iter().enumerate().any(|(y, row)| {
-                    let row = row
-                        .iter()
-                        .enumerate()
-                        .filter(|(_, &c)| c)
-                        .map(|(x, _)| x + position.0)
-                        .collect::<Vec<_>>();
-
-                    tower[(position.1 - 1 + y)].overlap(&row)
-                });
-
-            if !vertical_collision {
-                position.1 -= 1;
-            } else {
-                // Land the rock
-                for (y, row) in rock[0..rock.height].iter().enumerate() {
-                    let tower_row = &mut tower[position.1 + y];
-
-                    for x in row.iter().enumerate().filter(|(_, &c)| c).map(|(x, _)| x) {
-                        tower_row.set(position.0 + x);
-                    }
-                }
+        skyline
+    }
+}
 
-                break;
-            }
+/// Drops `num_rocks` rocks, returning the height of the tower. Once the chamber returns to a
+/// state it has already been in, the tower grows by the same amount every period, so the
+/// remaining whole periods are skipped.
+fn simulate(rocks: &[Rock], jets: &[Direction], num_rocks: usize) -> usize {
+    let mut chamber = Chamber::new(rocks, jets);
+    let mut seen: HashMap<(usize, usize, Skyline), (usize, usize)> = HashMap::new();
+    let mut skipped_height = None;
+
+    let mut dropped = 0;
+    while dropped < num_rocks {
+        chamber.drop_rock();
+        dropped += 1;
+
+        if skipped_height.is_some() {
+            continue;
+        }
+
+        let state = (chamber.next_rock, chamber.next_jet, chamber.skyline());
+        if let Some((previous_dropped, previous_height)) =
+            seen.insert(state, (dropped, chamber.height()))
+        {
+            let period = dropped - previous_dropped;
+            let cycles = (num_rocks - dropped) / period;
+
+            dropped += cycles * period;
+            skipped_height = Some(cycles * (chamber.height() - previous_height));
+        }
+    }
+
+    chamber.height() + skipped_height.unwrap_or(0)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Day17Params {
+    /// How many rocks fall in each part.
+    pub part_1_rocks: usize,
+    pub part_2_rocks: usize,
+}
+
+impl Default for Day17Params {
+    fn default() -> Self {
+        Self {
+            part_1_rocks: 2022,
+            part_2_rocks: 1000000000000,
         }
     }
+}
 
-    print_tower(&tower);
+impl Params for Day17Params {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), ParamError> {
+        match name {
+            "part_1_rocks" => self.part_1_rocks = param(name, value, "a number of rocks")?,
+            "part_2_rocks" => self.part_2_rocks = param(name, value, "a number of rocks")?,
+            _ => return Err(ParamError::Unknown(name.to_string())),
+        }
 
-    tower.into_iter().filter(|r| !r.is_empty()).count()
+        Ok(())
+    }
 }
 
 pub struct Day17;
 impl Day for Day17 {
-    type Input = (Vec<Rock>, Vec<Direction>);
-    type Output = usize;
+    const DAY: usize = 17;
+
+    type Input = (Vec<Rock>, Vec<Direction>, Day17Params);
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = Day17Params;
 
-    fn part_1((rocks, jets): Self::Input) -> Self::Output {
-        simulate(rocks, jets, NUM_ROCKS_1)
+    fn part_1((rocks, jets, params): &Self::Input) -> Self::Output1 {
+        simulate(rocks, jets, params.part_1_rocks)
     }
 
-    fn part_2((rocks, jets): Self::Input) -> Self::Output {
-        0
+    fn part_2((rocks, jets, params): &Self::Input) -> Self::Output2 {
+        simulate(rocks, jets, params.part_2_rocks)
     }
 
-    fn parse(raw: &str) -> Self::Input {
+    fn parse(raw: &str, params: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
         let rocks = ROCKS.split("\n\n").map(Rock::new).collect();
 
-        let jets = raw
-            .lines()
-            .next()
-            .unwrap()
-            .chars()
-            .map(Direction::from)
-            .collect();
+        let line = raw.lines().next().filter(|line| !line.is_empty());
+        let line = src.require(line, raw, "a jet pattern")?;
+        let jets = line
+            .char_indices()
+            .map(|(i, c)| match c {
+                '>' => Ok(Direction::Right),
+                '<' => Ok(Direction::Left),
+                _ => Err(src.error(&line[i..], "`<` or `>`")),
+            })
+            .collect::<Result<_, _>>()?;
 
-        (rocks, jets)
+        Ok((rocks, jets, params.clone()))
     }
 }
 
@@ -336,5 +245,17 @@ impl Day for Day17 {
 fn test() {
     let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
 
-    assert_eq!(Day17::run(input), (3068, 1514285714288));
+    assert_eq!(Day17::run(input), Ok((3068, 1514285714288)));
+
+    let params = Day17Params {
+        part_1_rocks: 1,
+        part_2_rocks: 10,
+    };
+    assert_eq!(Day17::run_with(input, &params), Ok((1, 17)));
+
+    // Without any jets there's nothing to push the rocks
+    for input in ["", "\n"] {
+        let error = Day17::parse(input, &Day17Params::default()).unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (1, "a jet pattern"));
+    }
 }
@@ -1,175 +1,498 @@
 use std::{
-    collections::{HashSet, VecDeque},
-    ops::Deref,
+    collections::{HashMap, VecDeque},
+    fmt::Write,
 };
 
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 
-#[derive(Clone)]
-pub struct Droplet {
-    cubes: HashSet<Point>,
-    x_bound: usize,
-    y_bound: usize,
-    z_bound: usize,
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
 }
 
-impl Deref for Droplet {
-    type Target = HashSet<Point>;
+impl Point {
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.cubes
+    /// The six cubes sharing a face with this one, in the same order as [`FACES`].
+    pub fn neighbours(self) -> [Point; 6] {
+        FACES.map(|([dx, dy, dz], _)| Point::new(self.x + dx, self.y + dy, self.z + dz))
+    }
+
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
+        let mut nums = raw.split(',');
+        let mut next_num = |expected| src.number(src.require(nums.next(), raw, expected)?);
+
+        let point = Self {
+            x: next_num("an x coordinate")?,
+            y: next_num("a y coordinate")?,
+            z: next_num("a z coordinate")?,
+        };
+
+        match nums.next() {
+            Some(extra) => Err(src.error(extra, "only three coordinates")),
+            None => Ok(point),
+        }
     }
 }
 
-impl From<&str> for Droplet {
-    fn from(value: &str) -> Self {
-        let mut x_bound = 0;
-        let mut y_bound = 0;
-        let mut z_bound = 0;
+/// The outward normal of each face of a unit cube, and its corners relative to the cube's
+/// lowest corner. Corners go anticlockwise when looking at the face from outside.
+const FACES: [([isize; 3], [[isize; 3]; 4]); 6] = [
+    ([-1, 0, 0], [[0, 0, 0], [0, 0, 1], [0, 1, 1], [0, 1, 0]]),
+    ([1, 0, 0], [[1, 0, 0], [1, 1, 0], [1, 1, 1], [1, 0, 1]]),
+    ([0, -1, 0], [[0, 0, 0], [1, 0, 0], [1, 0, 1], [0, 0, 1]]),
+    ([0, 1, 0], [[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]]),
+    ([0, 0, -1], [[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]]),
+    ([0, 0, 1], [[0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1]]),
+];
+
+/// Whether faces against air trapped inside the droplet count as exposed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pockets {
+    Include,
+    Exclude,
+}
 
-        Self {
-            cubes: value
-                .lines()
-                .map(|raw| {
-                    let cube: Point = raw.into();
+/// The exposed faces of a droplet, as squares with their outward normals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mesh {
+    pub faces: Vec<([isize; 3], [Point; 4])>,
+}
 
-                    x_bound = x_bound.max(cube.x);
-                    y_bound = y_bound.max(cube.y);
-                    z_bound = z_bound.max(cube.z);
+impl Mesh {
+    /// Renders the mesh as a Wavefront OBJ file, sharing vertices between faces.
+    pub fn obj(&self) -> String {
+        let mut vertices = HashMap::new();
+        let mut obj = String::new();
 
-                    cube
-                })
-                .collect(),
-            x_bound: x_bound + 1,
-            y_bound: y_bound + 1,
-            z_bound: z_bound + 1,
+        for ([x, y, z], _) in FACES {
+            writeln!(obj, "vn {x} {y} {z}").unwrap();
         }
+
+        let mut faces = String::new();
+        for (normal, corners) in &self.faces {
+            let n = FACES.iter().position(|(other, _)| other == normal).unwrap() + 1;
+
+            write!(faces, "f").unwrap();
+            for corner in corners {
+                let next = vertices.len() + 1;
+                let v = *vertices.entry(*corner).or_insert_with(|| {
+                    writeln!(obj, "v {} {} {}", corner.x, corner.y, corner.z).unwrap();
+                    next
+                });
+
+                write!(faces, " {v}//{n}").unwrap();
+            }
+            writeln!(faces).unwrap();
+        }
+
+        obj + &faces
+    }
+
+    /// Renders the mesh as an ASCII STL file, splitting each face into two triangles.
+    pub fn stl(&self) -> String {
+        let mut stl = "solid droplet\n".to_string();
+
+        for ([x, y, z], [a, b, c, d]) in &self.faces {
+            for triangle in [[a, b, c], [a, c, d]] {
+                writeln!(stl, "  facet normal {x} {y} {z}\n    outer loop").unwrap();
+                for corner in triangle {
+                    writeln!(stl, "      vertex {} {} {}", corner.x, corner.y, corner.z).unwrap();
+                }
+                writeln!(stl, "    endloop\n  endfacet").unwrap();
+            }
+        }
+
+        stl + "endsolid droplet\n"
     }
 }
 
-#[derive(Clone, Hash, Eq, PartialEq)]
-pub struct Point {
-    x: usize,
-    y: usize,
-    z: usize,
+/// A box of cubes, from `min` to `max` inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
 }
 
-impl Point {
-    pub fn neighbors(&self) -> HashSet<Point> {
-        [(0, 0, 1), (0, 1, 0), (1, 0, 0)]
-            .into_iter()
-            .flat_map(|(dx, dy, dz)| [(dx, dy, dz), (-dx, -dy, -dz)])
-            .filter_map(|(dx, dy, dz)| {
-                if let (Some(x), Some(y), Some(z)) = (
-                    self.x.checked_add_signed(dx),
-                    self.y.checked_add_signed(dy),
-                    self.z.checked_add_signed(dz),
-                ) {
-                    Some(Self { x, y, z })
-                } else {
-                    None
-                }
+impl Bounds {
+    /// The smallest box holding every point, if there are any.
+    pub fn around(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, p| {
+            let Some(Bounds { min, max }) = bounds else {
+                return Some(Bounds { min: p, max: p });
+            };
+
+            Some(Bounds {
+                min: Point::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                max: Point::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
             })
-            .collect()
+        })
+    }
+
+    /// The box with an extra layer of cubes on every side, if it fits within the coordinates.
+    pub fn grow(self) -> Option<Self> {
+        let Bounds { min, max } = self;
+
+        Some(Bounds {
+            min: Point::new(
+                min.x.checked_sub(1)?,
+                min.y.checked_sub(1)?,
+                min.z.checked_sub(1)?,
+            ),
+            max: Point::new(
+                max.x.checked_add(1)?,
+                max.y.checked_add(1)?,
+                max.z.checked_add(1)?,
+            ),
+        })
+    }
+
+    /// The number of cubes along each side, if they can be counted.
+    pub fn size(&self) -> Option<(usize, usize, usize)> {
+        Some((
+            self.min.x.abs_diff(self.max.x).checked_add(1)?,
+            self.min.y.abs_diff(self.max.y).checked_add(1)?,
+            self.min.z.abs_diff(self.max.z).checked_add(1)?,
+        ))
+    }
+
+    pub fn volume(&self) -> Option<usize> {
+        let (x, y, z) = self.size()?;
+
+        x.checked_mul(y)?.checked_mul(z)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
     }
 }
 
-impl From<&str> for Point {
-    fn from(value: &str) -> Self {
-        // Offset points by 1, to allow for edges against the 'border' (0)
-        let mut nums = value.split(',').map(|n| n.parse::<usize>().unwrap() + 1);
+/// The most cubes [`Voxels`] will hold, which takes 128 MiB.
+const MAX_VOXELS: usize = 1 << 30;
 
-        Self {
-            x: nums.next().unwrap(),
-            y: nums.next().unwrap(),
-            z: nums.next().unwrap(),
-        }
+/// A set of cubes within some bounds, stored as one bit per cube.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Voxels {
+    bounds: Bounds,
+    size: (usize, usize, usize),
+    bits: Vec<u64>,
+}
+
+impl Voxels {
+    /// Returns `None` if the bounds hold more than [`MAX_VOXELS`] cubes.
+    pub fn new(bounds: Bounds) -> Option<Self> {
+        let volume = bounds.volume().filter(|&volume| volume <= MAX_VOXELS)?;
+
+        Some(Self {
+            bounds,
+            size: bounds.size()?,
+            bits: vec![0; volume.div_ceil(u64::BITS as usize)],
+        })
+    }
+
+    /// The number of cubes that fit within the bounds.
+    pub fn volume(&self) -> usize {
+        let (x, y, z) = self.size;
+
+        x * y * z
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        let (width, height, _) = self.size;
+        let offset = |n: isize, min: isize| n.abs_diff(min);
+
+        self.bounds.contains(p).then(|| {
+            offset(p.x, self.bounds.min.x)
+                + width * (offset(p.y, self.bounds.min.y) + height * offset(p.z, self.bounds.min.z))
+        })
+    }
+
+    fn point(&self, i: usize) -> Point {
+        let (width, height, _) = self.size;
+        let min = self.bounds.min;
+
+        Point::new(
+            min.x + (i % width) as isize,
+            min.y + (i / width % height) as isize,
+            min.z + (i / width / height) as isize,
+        )
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.index(p)
+            .is_some_and(|i| self.bits[i / 64] & 1 << (i % 64) != 0)
+    }
+
+    /// Adds a cube, which must be within the bounds, returning whether it is new.
+    pub fn insert(&mut self, p: Point) -> bool {
+        let i = self.index(p).expect("voxel is out of bounds");
+        let new = self.bits[i / 64] & 1 << (i % 64) == 0;
+        self.bits[i / 64] |= 1 << (i % 64);
+
+        new
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(move |(word_index, &word)| {
+                (0..u64::BITS as usize)
+                    .filter(move |bit| word & 1 << bit != 0)
+                    .map(move |bit| self.point(word_index * 64 + bit))
+            })
     }
 }
 
-impl From<(usize, usize, usize)> for Point {
-    fn from((x, y, z): (usize, usize, usize)) -> Self {
-        Self { x, y, z }
+/// The lava droplet, with a layer of air kept around it so the outside can be flooded.
+#[derive(Clone, Debug)]
+pub struct Droplet {
+    cubes: Voxels,
+}
+
+impl Droplet {
+    /// Returns `None` if there are no cubes, or they are spread too far apart to hold in memory.
+    pub fn new(cubes: &[Point]) -> Option<Self> {
+        let bounds = Bounds::around(cubes.iter().copied())?.grow()?;
+
+        // Flooding the layer around the droplet looks one cube further out again
+        bounds.grow()?;
+
+        let mut voxels = Voxels::new(bounds)?;
+        for &cube in cubes {
+            voxels.insert(cube);
+        }
+
+        Some(Self { cubes: voxels })
+    }
+
+    /// The smallest box holding every cube.
+    pub fn bounds(&self) -> Bounds {
+        let Bounds { min, max } = self.cubes.bounds;
+
+        Bounds {
+            min: Point::new(min.x + 1, min.y + 1, min.z + 1),
+            max: Point::new(max.x - 1, max.y - 1, max.z - 1),
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cubes.contains(p)
+    }
+
+    pub fn cubes(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cubes.iter()
+    }
+
+    /// Fills the air connected to `start` that isn't in `filled` yet, passing each cube filled to
+    /// `visit`. Going breadth first keeps the queue to a wavefront rather than the whole volume.
+    fn flood(&self, start: Point, filled: &mut Voxels, mut visit: impl FnMut(Point)) {
+        let mut queue = VecDeque::from([start]);
+        filled.insert(start);
+
+        while let Some(p) = queue.pop_front() {
+            visit(p);
+
+            for n in p.neighbours() {
+                if self.cubes.bounds.contains(n) && !self.contains(n) && filled.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    /// The air outside the droplet, within the layer around it.
+    pub fn exterior(&self) -> Voxels {
+        let mut exterior = Voxels::new(self.cubes.bounds).unwrap();
+        self.flood(self.cubes.bounds.min, &mut exterior, |_| {});
+
+        exterior
+    }
+
+    /// Pockets of air trapped inside the droplet, each as the air cubes it is made of.
+    pub fn air_pockets(&self) -> Vec<Vec<Point>> {
+        let mut filled = self.exterior();
+        let mut pockets = Vec::new();
+
+        // Anything outside the bounds is connected to the exterior
+        let bounds = self.bounds();
+        for p in (0..self.cubes.volume())
+            .map(|i| self.cubes.point(i))
+            .filter(|&p| bounds.contains(p))
+        {
+            if !self.contains(p) && !filled.contains(p) {
+                let mut pocket = Vec::new();
+                self.flood(p, &mut filled, |air| pocket.push(air));
+                pockets.push(pocket);
+            }
+        }
+
+        pockets
+    }
+
+    /// Every face of a cube that isn't against another cube.
+    pub fn surface_area(&self) -> usize {
+        self.cubes()
+            .flat_map(Point::neighbours)
+            .filter(|&n| !self.contains(n))
+            .count()
+    }
+
+    /// The faces of cubes that can be reached from outside the droplet, so not counting those
+    /// facing into an air pocket.
+    pub fn exterior_surface_area(&self) -> usize {
+        let trapped = self
+            .air_pockets()
+            .into_iter()
+            .flatten()
+            .flat_map(Point::neighbours)
+            .filter(|&n| self.contains(n))
+            .count();
+
+        self.surface_area() - trapped
+    }
+
+    /// Builds a mesh of every exposed face, which can be opened in a 3D viewer.
+    pub fn mesh(&self, pockets: Pockets) -> Mesh {
+        let exterior = self.exterior();
+
+        let faces = self
+            .cubes()
+            .flat_map(|cube| {
+                cube.neighbours()
+                    .into_iter()
+                    .zip(FACES)
+                    .map(move |f| (cube, f))
+            })
+            .filter(|&(_, (n, _))| {
+                !self.contains(n) && (pockets == Pockets::Include || exterior.contains(n))
+            })
+            .map(|(cube, (_, (normal, corners)))| {
+                let corners =
+                    corners.map(|[dx, dy, dz]| Point::new(cube.x + dx, cube.y + dy, cube.z + dz));
+
+                (normal, corners)
+            })
+            .collect();
+
+        Mesh { faces }
     }
 }
 
 pub struct Day18;
 impl Day for Day18 {
+    const DAY: usize = 18;
+
     type Input = Droplet;
-    type Output = usize;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        input
-            .iter()
-            .map(|cube| {
-                let neighbors = cube
-                    .neighbors()
-                    .into_iter()
-                    .filter(|c| input.contains(c))
-                    .count();
+    fn part_1(droplet: &Self::Input) -> Self::Output1 {
+        droplet.surface_area()
+    }
 
-                6 - neighbors
-            })
-            .sum()
-    }
-
-    fn part_2(input: Self::Input) -> Self::Output {
-        let mut visited = HashSet::new();
-        let mut frontier = VecDeque::new();
-        frontier.push_back(Point::from((0, 0, 0)));
-
-        let mut touched = 0;
-
-        while let Some(p) = frontier.pop_back() {
-            visited.insert(p.clone());
-
-            let (air, mass) = p.neighbors().into_iter().fold(
-                (HashSet::new(), HashSet::new()),
-                |(mut air, mut mass), n| {
-                    if input.contains(&n) {
-                        mass.insert(n);
-                    } else {
-                        air.insert(n);
-                    }
-
-                    (air, mass)
-                },
-            );
-
-            touched += mass.len();
-
-            let air: HashSet<Point> = air
-                .into_iter()
-                .filter(|n| {
-                    !visited.contains(n)
-                        && !frontier.contains(n)
-                        && n.x <= input.x_bound
-                        && n.y <= input.y_bound
-                        && n.z <= input.z_bound
-                })
-                .collect();
-
-            frontier.extend(air);
+    fn part_2(droplet: &Self::Input) -> Self::Output2 {
+        droplet.exterior_surface_area()
+    }
+
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        let cubes = raw
+            .lines()
+            .map(|line| Point::parse(&src, line))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if cubes.is_empty() {
+            return Err(src.error(raw, "a cube"));
         }
 
-        touched
+        src.require(
+            Droplet::new(&cubes),
+            raw,
+            "a droplet small enough to hold in memory",
+        )
     }
+}
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.into()
+#[test]
+fn test_single() {
+    assert_eq!(Day18::run("1,1,1"), Ok((6, 6)));
+}
+#[test]
+fn test_negative() {
+    assert_eq!(Day18::run("-1,0,0\n0,0,0"), Ok((10, 10)));
+}
+#[test]
+fn test_too_large() {
+    for input in ["9223372036854775807,0,0", "0,0,0\n100000,100000,100000"] {
+        let error = Day18::parse(input, &()).unwrap_err();
+        assert_eq!(error.expected, "a droplet small enough to hold in memory");
     }
+
+    // Far larger than the puzzle's, but still fits
+    assert_eq!(Day18::run("0,0,0\n60,60,60"), Ok((12, 12)));
 }
+#[test]
+fn test_hollow() {
+    // A 10x10x10 shell, one cube thick, around a single pocket of air
+    let shell = (0..10)
+        .flat_map(|x| (0..10).flat_map(move |y| (0..10).map(move |z| Point::new(x, y, z))))
+        .filter(|p| [p.x, p.y, p.z].iter().any(|&n| n == 0 || n == 9))
+        .collect::<Vec<_>>();
+    let droplet = Droplet::new(&shell).unwrap();
 
+    assert_eq!(droplet.surface_area(), 600 + 384);
+    assert_eq!(droplet.exterior_surface_area(), 600);
+    assert_eq!(
+        droplet
+            .air_pockets()
+            .iter()
+            .map(Vec::len)
+            .collect::<Vec<_>>(),
+        vec![512]
+    );
+    assert_eq!(droplet.mesh(Pockets::Include).faces.len(), 600 + 384);
+    assert_eq!(droplet.mesh(Pockets::Exclude).faces.len(), 600);
+}
 #[test]
-fn test_single() {
-    assert_eq!(Day18::run("1,1,1"), (6, 6));
+fn test_mesh() {
+    let droplet = Day18::parse("1,1,1\n2,1,1", &()).unwrap();
+    let mesh = droplet.mesh(Pockets::Include);
+    assert_eq!(mesh.faces.len(), 10);
+
+    // Corners shared between faces are only written once
+    let obj = mesh.obj();
+    assert_eq!(
+        obj.lines().filter(|line| line.starts_with("v ")).count(),
+        12
+    );
+    assert_eq!(
+        obj.lines().filter(|line| line.starts_with("f ")).count(),
+        10
+    );
+
+    let stl = mesh.stl();
+    assert!(stl.starts_with("solid droplet\n  facet normal -1 0 0\n    outer loop\n"));
+    assert!(stl.ends_with("endsolid droplet\n"));
+    assert_eq!(stl.matches("endfacet").count(), 20);
 }
 #[test]
 fn test_small() {
     let input = "1,1,1
 2,1,1";
 
-    assert_eq!(Day18::run(input), (10, 10));
+    assert_eq!(Day18::run(input), Ok((10, 10)));
 }
 #[test]
 fn test_large() {
@@ -187,7 +510,17 @@ fn test_large() {
 2,1,5
 2,3,5";
 
-    assert_eq!(Day18::run(input), (64, 58));
+    assert_eq!(Day18::run(input), Ok((64, 58)));
+
+    let droplet = Day18::parse(input, &()).unwrap();
+    assert_eq!(droplet.air_pockets(), vec![vec![Point::new(2, 2, 5)]]);
+    assert_eq!(
+        droplet.bounds(),
+        Bounds {
+            min: Point::new(1, 1, 1),
+            max: Point::new(3, 3, 6)
+        }
+    );
 }
 #[test]
 fn test_larger() {
@@ -227,5 +560,5 @@ fn test_larger() {
 4,3,3
 ";
 
-    assert_eq!(Day18::run(input), (76, 66));
+    assert_eq!(Day18::run(input), Ok((76, 66)));
 }
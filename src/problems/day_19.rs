@@ -1,90 +1,123 @@
-use crate::day::Day;
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
+};
 use std::{
-    collections::{HashMap, VecDeque},
-    hash::Hash,
-    ops::{Add, AddAssign, Sub, SubAssign},
+    array,
+    fmt::Display,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Sub, SubAssign},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
 };
 
-#[derive(Clone, Debug)]
-pub struct MaterialCollection {
-    ore: usize,
-    clay: usize,
-    obsidian: usize,
-    geode: usize,
+/// The most kinds of material a single blueprint can mention.
+const MAX_MATERIALS: usize = 8;
+
+/// A kind of material, as its position in a blueprint's [`Materials`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Material(usize);
+
+/// The names of the materials a blueprint mentions, in the order they first appear.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Materials {
+    names: Vec<String>,
 }
-impl MaterialCollection {
-    pub fn new() -> Self {
-        Self {
-            ore: 0,
-            clay: 0,
-            obsidian: 0,
-            geode: 0,
-        }
+
+impl Materials {
+    pub fn get(&self, name: &str) -> Option<Material> {
+        self.names.iter().position(|n| n == name).map(Material)
     }
 
-    pub fn mine(&mut self, material: Material, amount: usize) {
-        match material {
-            Material::Ore => self.ore += amount,
-            Material::Clay => self.clay += amount,
-            Material::Obsidian => self.obsidian += amount,
-            Material::Geode => self.geode += amount,
-        }
+    pub fn name(&self, material: Material) -> &str {
+        &self.names[material.0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Material> {
+        (0..self.names.len()).map(Material)
     }
 
-    pub fn get(&self, material: &Material) -> usize {
-        match material {
-            Material::Ore => self.ore,
-            Material::Clay => self.clay,
-            Material::Obsidian => self.obsidian,
-            Material::Geode => self.geode,
+    /// Finds the material called `raw`, adding it if this is the first time it's been seen.
+    fn parse(&mut self, src: &Source, raw: &str) -> Result<Material, ParseError> {
+        if let Some(material) = self.get(raw) {
+            return Ok(material);
+        }
+
+        if self.names.len() == MAX_MATERIALS {
+            return Err(src.error(
+                raw,
+                format!("one of the first {MAX_MATERIALS} materials mentioned"),
+            ));
         }
+
+        self.names.push(raw.to_string());
+        Ok(Material(self.names.len() - 1))
+    }
+}
+
+/// An amount of each material, such as what's in stock or what a robot costs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MaterialCollection([usize; MAX_MATERIALS]);
+
+impl MaterialCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn can_afford(&self, cost: &Self) -> bool {
+        self.0.iter().zip(cost.0).all(|(&have, need)| have >= need)
     }
 
     pub fn max(&mut self, other: &Self) {
-        self.ore = self.ore.max(other.ore);
-        self.clay = self.clay.max(other.clay);
-        self.obsidian = self.obsidian.max(other.obsidian);
-        self.geode = self.geode.max(other.geode);
+        *self = self.zip_with(*other, usize::max);
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(usize, usize) -> usize) -> Self {
+        Self(array::from_fn(|i| f(self.0[i], other.0[i])))
     }
 }
-impl Add<&MaterialCollection> for MaterialCollection {
+impl Index<Material> for MaterialCollection {
+    type Output = usize;
+
+    fn index(&self, material: Material) -> &Self::Output {
+        &self.0[material.0]
+    }
+}
+impl IndexMut<Material> for MaterialCollection {
+    fn index_mut(&mut self, material: Material) -> &mut Self::Output {
+        &mut self.0[material.0]
+    }
+}
+impl Add for MaterialCollection {
     type Output = Self;
 
-    fn add(self, rhs: &MaterialCollection) -> Self::Output {
-        Self {
-            ore: self.ore + rhs.ore,
-            clay: self.clay + rhs.clay,
-            obsidian: self.obsidian + rhs.obsidian,
-            geode: self.geode + rhs.geode,
-        }
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
     }
 }
 impl AddAssign for MaterialCollection {
     fn add_assign(&mut self, rhs: Self) {
-        self.ore += rhs.ore;
-        self.clay += rhs.clay;
-        self.obsidian += rhs.obsidian;
-        self.geode += rhs.geode;
+        *self = *self + rhs;
     }
 }
-impl Sub<&MaterialCollection> for MaterialCollection {
+impl Sub for MaterialCollection {
     type Output = Self;
 
-    fn sub(self, rhs: &MaterialCollection) -> Self::Output {
-        Self {
-            ore: self.ore.saturating_sub(rhs.ore),
-            clay: self.clay.saturating_sub(rhs.clay),
-            obsidian: self.obsidian.saturating_sub(rhs.obsidian),
-            geode: self.geode.saturating_sub(rhs.geode),
-        }
+    /// Panics if there isn't enough of something, which [`MaterialCollection::can_afford`]
+    /// checks for.
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
     }
 }
 impl SubAssign for MaterialCollection {
     fn sub_assign(&mut self, rhs: Self) {
-        self.ore = self.ore.saturating_sub(rhs.ore);
-        self.clay = self.clay.saturating_sub(rhs.clay);
-        self.obsidian = self.obsidian.saturating_sub(rhs.obsidian);
-        self.geode = self.geode.saturating_sub(rhs.geode);
+        *self = *self - rhs;
+    }
+}
+impl Mul<usize> for MaterialCollection {
+    type Output = Self;
+
+    fn mul(self, rhs: usize) -> Self::Output {
+        Self(self.0.map(|amount| amount * rhs))
     }
 }
 impl FromIterator<(Material, usize)> for MaterialCollection {
@@ -92,7 +125,7 @@ impl FromIterator<(Material, usize)> for MaterialCollection {
         let mut collection = MaterialCollection::new();
 
         for (material, amount) in iter.into_iter() {
-            collection.mine(material, amount);
+            collection[material] += amount;
         }
 
         collection
@@ -102,21 +135,48 @@ impl FromIterator<(Material, usize)> for MaterialCollection {
 #[derive(Clone, Debug)]
 pub struct Blueprint {
     id: usize,
+    materials: Materials,
     robots: Vec<RobotBlueprint>,
+    /// What the first robot collects.
+    ore: Material,
+    /// What to collect as much of as possible.
+    geode: Material,
 }
 
-impl From<&str> for Blueprint {
-    fn from(value: &str) -> Self {
-        let (lhs, rhs) = value.split_once(':').unwrap();
+impl Blueprint {
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
+        let (lhs, rhs) = src.split_once(raw, ":")?;
+
+        let id = src.number(src.strip_prefix(lhs, "Blueprint ")?)?;
 
-        let id = lhs.split_once(' ').unwrap().1.parse().unwrap();
+        let mut materials = Materials::default();
         let robots = rhs
             .split('.')
-            .take_while(|s| !s.is_empty())
-            .map(RobotBlueprint::from)
-            .collect();
+            .take_while(|s| !s.trim().is_empty())
+            .map(|robot| RobotBlueprint::parse(src, robot, &mut materials))
+            .collect::<Result<_, _>>()?;
+
+        let ore = src.require(materials.get("ore"), raw, "a mention of ore")?;
+        let geode = src.require(materials.get("geode"), raw, "a mention of geodes")?;
+
+        Ok(Self {
+            id,
+            materials,
+            robots,
+            ore,
+            geode,
+        })
+    }
+
+    /// The robot that gathers `material`, as the puzzle describes it.
+    fn robot_name(&self, material: Material) -> String {
+        let name = self.materials.name(material);
 
-        Self { id, robots }
+        if material == self.geode {
+            format!("{name}-cracking robot")
+        } else {
+            format!("{name}-collecting robot")
+        }
     }
 }
 
@@ -126,222 +186,377 @@ pub struct RobotBlueprint {
     requirements: MaterialCollection,
 }
 
-impl From<&str> for RobotBlueprint {
-    fn from(value: &str) -> Self {
-        let (lhs, rhs) = value.split_once(" robot costs ").unwrap();
+impl RobotBlueprint {
+    pub fn parse(src: &Source, raw: &str, materials: &mut Materials) -> Result<Self, ParseError> {
+        let raw = src.strip_prefix(raw.trim_start(), "Each ")?;
+        let (robot, requirements) = src.split_once(raw, " robot costs ")?;
 
-        let robot = lhs.trim().split_once(' ').unwrap().1.into();
+        let robot = materials.parse(src, robot)?;
 
-        let requirements = rhs
-            .trim_matches('.')
+        let requirements = requirements
             .split(" and ")
             .map(|req| {
-                let (amount, material) = req.split_once(' ').unwrap();
+                let (amount, material) = src.split_once(req, " ")?;
 
-                let amount: usize = amount.parse().unwrap();
-                let material: Material = material.into();
+                let amount: usize = src.number(amount)?;
+                let material = materials.parse(src, material)?;
 
-                (material, amount)
+                Ok((material, amount))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        Self {
+        Ok(Self {
             robot,
             requirements,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    robots: MaterialCollection,
+    materials: MaterialCollection,
+    time_remaining: usize,
+}
+
+impl State {
+    /// Geodes opened by the end if no more robots are built.
+    fn geodes(&self, blueprint: &Blueprint) -> usize {
+        self.materials[blueprint.geode] + self.robots[blueprint.geode] * self.time_remaining
+    }
+
+    /// How long until `robot` has been built, if it can be built at all with the current robots.
+    fn time_to_build(&self, blueprint: &Blueprint, robot: &RobotBlueprint) -> Option<usize> {
+        let mut wait = 0;
+
+        for material in blueprint.materials.iter() {
+            let required = robot.requirements[material];
+            let available = self.materials[material];
+
+            if required > available {
+                let production = self.robots[material];
+                if production == 0 {
+                    return None;
+                }
+
+                wait = wait.max((required - available).div_ceil(production));
+            }
+        }
+
+        // One more minute to build it
+        Some(wait + 1)
+    }
+
+    /// Waits for `robot` to be built, mining in the meantime.
+    fn build(&self, robot: &RobotBlueprint, time: usize) -> Self {
+        let mut robots = self.robots;
+        robots[robot.robot] += 1;
+
+        Self {
+            robots,
+            materials: self.materials + self.robots * time - robot.requirements,
+            time_remaining: self.time_remaining - time,
+        }
+    }
+
+    /// The most geodes that could possibly be opened from here. Each kind of robot is paid for
+    /// from its own copy of the materials, and one of every kind that can be afforded is built
+    /// each minute, which can only ever do better than building one robot at a time.
+    fn upper_bound(&self, blueprint: &Blueprint) -> usize {
+        let mut robots = self.robots;
+        let mut funds = vec![self.materials; blueprint.robots.len()];
+
+        for _ in 0..self.time_remaining {
+            let mut built = MaterialCollection::new();
+            for (robot, funds) in blueprint.robots.iter().zip(&mut funds) {
+                if funds.can_afford(&robot.requirements) {
+                    *funds -= robot.requirements;
+                    built[robot.robot] += 1;
+                }
+            }
+
+            for funds in &mut funds {
+                *funds += robots;
+            }
+            robots += built;
         }
+
+        funds
+            .iter()
+            .map(|funds| funds[blueprint.geode])
+            .max()
+            .unwrap_or_else(|| self.geodes(blueprint))
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub enum Material {
-    Ore,
-    Clay,
-    Obsidian,
-    Geode,
+/// A robot being built, and what's in stock at the end of the minute it was built in.
+#[derive(Clone, Debug)]
+pub struct Build<'a> {
+    minute: usize,
+    robot: &'a RobotBlueprint,
+    materials: MaterialCollection,
 }
-impl Material {
-    #[inline]
-    pub fn options() -> impl Iterator<Item = Self> {
-        use Material::*;
 
-        [Ore, Clay, Obsidian, Geode].into_iter()
+#[allow(dead_code)]
+impl<'a> Build<'a> {
+    pub fn minute(&self) -> usize {
+        self.minute
+    }
+
+    pub fn robot(&self) -> &'a RobotBlueprint {
+        self.robot
+    }
+
+    pub fn materials(&self) -> &MaterialCollection {
+        &self.materials
     }
 }
-impl From<&str> for Material {
-    fn from(value: &str) -> Self {
-        use Material::*;
-
-        match value {
-            "ore" => Ore,
-            "clay" => Clay,
-            "obsidian" => Obsidian,
-            "geode" => Geode,
-            _ => unreachable!(),
-        }
+
+/// The order to build robots in to open the most geodes in the time given.
+#[derive(Clone, Debug)]
+pub struct Schedule<'a> {
+    blueprint: &'a Blueprint,
+    time: usize,
+    geodes: usize,
+    builds: Vec<Build<'a>>,
+}
+
+#[allow(dead_code)]
+impl<'a> Schedule<'a> {
+    pub fn geodes(&self) -> usize {
+        self.geodes
+    }
+
+    pub fn builds(&self) -> &[Build<'a>] {
+        &self.builds
     }
 }
 
-fn solve(blueprints: &[Blueprint], time: usize) -> HashMap<usize, usize> {
-    #[derive(Debug)]
-    struct State<'a> {
-        robots: MaterialCollection,
-        materials: MaterialCollection,
-        time_remaining: usize,
-        blueprint: &'a Blueprint,
-    }
-
-    let mut frontier = VecDeque::new();
-    for blueprint in blueprints {
-        frontier.push_back(State {
-            robots: MaterialCollection::from_iter([(Material::Ore, 1)]),
-            materials: MaterialCollection::new(),
-            time_remaining: time,
-            blueprint,
-        });
-    }
-
-    let mut largest: HashMap<usize, usize> = HashMap::new();
-
-    while let Some(state) = frontier.pop_front() {
-        // Determine the maximum requirements for each material type
-        let max_requirements = state.blueprint.robots.iter().fold(
-            MaterialCollection::new(),
-            |mut max_requirements, blueprint| {
-                max_requirements.max(&blueprint.requirements);
-                max_requirements
-            },
-        );
-
-        // Calculate time for each possible type of robot to be generated
-        let next_states = state.blueprint.robots.iter().filter_map(|robot_blueprint| {
-            // Check if there's a current need for the robot
-            if robot_blueprint.robot != Material::Geode
-                && state.robots.get(&robot_blueprint.robot)
-                    >= max_requirements.get(&robot_blueprint.robot)
-            {
-                return None;
+impl Display for Schedule<'_> {
+    /// Retells the schedule minute by minute, in the same words as the puzzle.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let blueprint = self.blueprint;
+        let name = |material| blueprint.materials.name(material);
+        let plural = |n| if n == 1 { "" } else { "s" };
+        let verb = |n| if n == 1 { "s" } else { "" };
+
+        let mut robots = MaterialCollection::from_iter([(blueprint.ore, 1)]);
+        let mut materials = MaterialCollection::new();
+        let mut builds = self.builds.iter().peekable();
+
+        for minute in 1..=self.time {
+            if minute > 1 {
+                writeln!(f)?;
             }
+            writeln!(f, "== Minute {minute} ==")?;
+
+            let build = builds.next_if(|build| build.minute == minute);
+            if let Some(Build { robot, .. }) = build {
+                let cost = blueprint
+                    .materials
+                    .iter()
+                    .filter(|&material| robot.requirements[material] > 0)
+                    .map(|material| format!("{} {}", robot.requirements[material], name(material)))
+                    .collect::<Vec<_>>()
+                    .join(" and ");
+                let robot_name = blueprint.robot_name(robot.robot);
+                let article = if robot_name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                    "an"
+                } else {
+                    "a"
+                };
 
-            let material_times = Material::options()
-                .filter_map(|material| {
-                    let required_material = robot_blueprint.requirements.get(&material);
-                    let available_material = state.materials.get(&material);
-
-                    if required_material > available_material {
-                        // Still have to produce material
-                        let remaining_material = required_material - available_material;
-
-                        let material_production = state.robots.get(&material);
-                        if material_production > 0 {
-                            return Some(
-                                (remaining_material / material_production)
-                                    + if remaining_material % material_production == 0 {
-                                        0
-                                    } else {
-                                        1
-                                    },
-                            );
-                        }
-
-                        None
-                    } else {
-                        // Robot can be immediately built
-                        Some(0)
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            // Check if it's possible to create all materials
-            if material_times.len() == 4 {
-                // Add one step for making robot
-                let time_to_create = material_times.into_iter().max().unwrap() + 1;
-
-                if time_to_create <= state.time_remaining {
-                    let new_state = State {
-                        time_remaining: state.time_remaining - time_to_create,
-                        materials: {
-                            let mut materials = state.materials.clone();
-
-                            // Mine materials
-                            Material::options().for_each(|material| {
-                                materials
-                                    .mine(material, state.robots.get(&material) * time_to_create)
-                            });
-
-                            // Spend on robot creation
-                            materials -= robot_blueprint.requirements.clone();
-
-                            materials
-                        },
-                        robots: {
-                            let mut robots = state.robots.clone();
-                            robots.mine(robot_blueprint.robot, 1);
-                            robots
-                        },
-                        blueprint: state.blueprint,
-                    };
-
-                    // Make sure it's possible to beat the current best
-                    let max_geodes = new_state.materials.get(&Material::Geode) // Current geodes
-                            // Geodes produced
-                        + (new_state.robots.get(&Material::Geode) * new_state.time_remaining)
-                            // Max possible if geode robot greated every turn
-                        + (new_state.time_remaining * (new_state.time_remaining - 1) / 2);
-
-                    if largest
-                        .get(&new_state.blueprint.id)
-                        .cloned()
-                        .unwrap_or_default()
-                        >= max_geodes
-                    {
-                        return None;
-                    }
+                writeln!(f, "Spend {cost} to start building {article} {robot_name}.")?;
+                materials -= robot.requirements;
+            }
 
-                    if new_state.time_remaining == 0 {
-                        let largest = largest.entry(new_state.blueprint.id).or_default();
-                        *largest = *largest.max(&mut new_state.materials.geode.to_owned());
+            for material in blueprint.materials.iter() {
+                let count = robots[material];
+                if count == 0 {
+                    continue;
+                }
 
-                        None
-                    } else {
-                        Some(new_state)
-                    }
+                materials[material] += count;
+                let total = materials[material];
+                let robot_name = blueprint.robot_name(material);
+
+                if material == blueprint.geode {
+                    writeln!(
+                        f,
+                        "{count} {robot_name}{} crack{} {count} {}{}; you now have {total} open {}{}.",
+                        plural(count),
+                        verb(count),
+                        name(material),
+                        plural(count),
+                        name(material),
+                        plural(total),
+                    )?;
                 } else {
-                    None
+                    writeln!(
+                        f,
+                        "{count} {robot_name}{} collect{} {count} {}; you now have {total} {}.",
+                        plural(count),
+                        verb(count),
+                        name(material),
+                        name(material),
+                    )?;
                 }
-            } else {
-                None
             }
-        });
 
-        frontier.extend(next_states);
-    }
+            if let Some(build) = build {
+                debug_assert_eq!(
+                    materials, build.materials,
+                    "stock differs at minute {minute}"
+                );
+
+                robots[build.robot.robot] += 1;
+                writeln!(
+                    f,
+                    "The new {} is ready; you now have {} of them.",
+                    blueprint.robot_name(build.robot.robot),
+                    robots[build.robot.robot]
+                )?;
+            }
+        }
 
-    largest
+        Ok(())
+    }
 }
 
-pub struct Day19;
-impl Day for Day19 {
-    type Input = Vec<Blueprint>;
-    type Output = usize;
+/// Depth first search over which robot to build next, abandoning any branch that can't beat
+/// the best found so far. `builds` are those made to reach `state`.
+fn search<'a>(
+    max_robots: &MaterialCollection,
+    state: State,
+    builds: &mut Vec<Build<'a>>,
+    best: &mut Schedule<'a>,
+) {
+    let blueprint = best.blueprint;
+
+    if state.geodes(blueprint) > best.geodes {
+        best.geodes = state.geodes(blueprint);
+        best.builds.clone_from(builds);
+    }
+
+    if state.upper_bound(blueprint) <= best.geodes {
+        return;
+    }
 
-    fn part_1(input: Self::Input) -> Self::Output {
-        let largest = solve(&input, 24);
+    // Geode robots are usually listed last, and trying them first finds good answers sooner
+    for robot in blueprint.robots.iter().rev() {
+        // There's no use in mining more of a material than can be spent in a minute
+        if robot.robot != blueprint.geode && state.robots[robot.robot] >= max_robots[robot.robot] {
+            continue;
+        }
 
-        dbg!(&largest);
+        match state.time_to_build(blueprint, robot) {
+            Some(time) if time < state.time_remaining => {
+                let next = state.build(robot, time);
+                builds.push(Build {
+                    minute: best.time - next.time_remaining,
+                    robot,
+                    materials: next.materials,
+                });
+
+                search(max_robots, next, builds, best);
+                builds.pop();
+            }
+            _ => {}
+        }
+    }
+}
 
-        largest.into_iter().map(|(id, amount)| id * amount).sum()
+/// The schedule opening the most geodes the blueprint can in the time given.
+pub fn best_schedule(blueprint: &Blueprint, time: usize) -> Schedule<'_> {
+    let mut max_robots = MaterialCollection::new();
+    for robot in &blueprint.robots {
+        max_robots.max(&robot.requirements);
     }
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        let largest = solve(&input.into_iter().take(3).collect::<Vec<_>>(), 32);
+    let start = State {
+        robots: MaterialCollection::from_iter([(blueprint.ore, 1)]),
+        materials: MaterialCollection::new(),
+        time_remaining: time,
+    };
+
+    let mut best = Schedule {
+        blueprint,
+        time,
+        geodes: 0,
+        builds: Vec::new(),
+    };
+    search(&max_robots, start, &mut Vec::new(), &mut best);
+
+    best
+}
 
-        dbg!(&largest);
+/// The best schedule for each blueprint, in the same order, searching blueprints in parallel.
+fn solve(blueprints: &[Blueprint], time: usize) -> Vec<Schedule<'_>> {
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(blueprints.len());
+
+    let mut schedules = thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut solved = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(blueprint) = blueprints.get(i) else {
+                            return solved;
+                        };
+
+                        solved.push((i, best_schedule(blueprint, time)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    schedules.sort_unstable_by_key(|&(i, _)| i);
+
+    schedules
+        .into_iter()
+        .map(|(_, schedule)| schedule)
+        .collect()
+}
 
-        largest.values().product()
+pub struct Day19;
+impl Day for Day19 {
+    const DAY: usize = 19;
+
+    type Input = Vec<Blueprint>;
+    type Output1 = usize;
+    type Output2 = usize;
+    type Params = ();
+
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        input
+            .iter()
+            .zip(solve(input, 24))
+            .map(|(blueprint, schedule)| blueprint.id * schedule.geodes)
+            .sum()
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.lines().map(Blueprint::from).collect()
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        solve(&input[..input.len().min(3)], 32)
+            .iter()
+            .map(|schedule| schedule.geodes)
+            .product()
+    }
+
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
+
+        raw.lines()
+            .map(|line| Blueprint::parse(&src, line))
+            .collect()
     }
 }
 
@@ -350,5 +565,67 @@ fn test() {
     let input = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
 Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
 
-    assert_eq!(Day19::run(input), (33, 3472));
+    assert_eq!(Day19::run(input), Ok((33, 3472)));
+
+    let blueprints = Day19::parse(input, &()).unwrap();
+    assert_eq!(
+        solve(&blueprints, 24)
+            .iter()
+            .map(|schedule| schedule.geodes)
+            .collect::<Vec<_>>(),
+        vec![9, 12]
+    );
+
+    let schedule = best_schedule(&blueprints[1], 32);
+    assert_eq!(schedule.geodes(), 62);
+    assert_eq!(schedule.builds().first().unwrap().minute(), 3);
+
+    let last = schedule.builds().last().unwrap();
+    assert_eq!(last.robot().robot, blueprints[1].geode);
+    assert!(last.materials()[blueprints[1].geode] < 62);
+
+    let narrative = best_schedule(&blueprints[0], 24).to_string();
+    assert!(narrative.starts_with(
+        "== Minute 1 ==
+1 ore-collecting robot collects 1 ore; you now have 1 ore.
+
+== Minute 2 ==
+1 ore-collecting robot collects 1 ore; you now have 2 ore.
+"
+    ));
+    assert!(
+        narrative.contains("Spend 2 ore and 7 obsidian to start building a geode-cracking robot.")
+    );
+    assert!(narrative.ends_with("geodes; you now have 9 open geodes.\n"));
+}
+#[test]
+fn test_materials() {
+    // Any materials can be used, as long as there's ore to start with and geodes to open
+    let blueprints = Day19::parse(
+        "Blueprint 1: Each ore robot costs 10 ore. Each geode robot costs 2 ore.
+Blueprint 2: Each ore robot costs 2 ore. Each sand robot costs 1 ore. Each glass robot costs 3 sand. Each geode robot costs 1 ore and 2 glass.",
+        &(),
+    )
+    .unwrap();
+    assert_eq!(
+        blueprints[1].materials.names,
+        ["ore", "sand", "glass", "geode"]
+    );
+
+    let schedule = best_schedule(&blueprints[0], 6);
+    assert_eq!(schedule.geodes, 4);
+    assert_eq!(
+        schedule
+            .builds
+            .iter()
+            .map(|build| build.minute)
+            .collect::<Vec<_>>(),
+        vec![3, 5]
+    );
+    assert!(schedule
+        .to_string()
+        .ends_with("2 geode-cracking robots crack 2 geodes; you now have 4 open geodes.\n"));
+    assert_eq!(best_schedule(&blueprints[1], 12).geodes, 10);
+
+    assert!(Day19::parse("Blueprint 1: Each ore robot costs 1 ore.", &()).is_err());
 }
@@ -1,103 +1,60 @@
-use crate::day::Day;
-
-pub fn wrapping_add(n: usize, d: isize, max: usize) -> usize {
-    ((max + n) as isize + (d % max as isize)) as usize % max
-}
-
-fn mix(original: &[isize], decryption_key: usize, amount: usize) -> isize {
-    let mut message = original
-        .iter()
-        .map(|&n| n * decryption_key as isize)
-        .enumerate()
-        .collect::<Vec<_>>();
-
-    for _ in 0..amount {
-        for (id, offset) in original
-            .iter()
-            .map(|&n| n * decryption_key as isize)
-            .enumerate()
-        {
-            let current_index = message
-                .iter()
-                .enumerate()
-                .find(|&(_, (original_id, _))| *original_id == id)
-                .map(|(i, _)| i)
-                .unwrap();
-            let new_index = wrapping_add(current_index, offset, message.len() - 1);
-
-            let item = message.remove(current_index);
-            message.insert(new_index, item);
-        }
+use crate::{
+    day::Day,
+    mixer::CircularMixer,
+    parse::{ParseError, Source},
+};
+
+const DECRYPTION_KEY: isize = 811589153;
+const GROVE_OFFSETS: [usize; 3] = [1000, 2000, 3000];
+
+/// Mixes the message `rounds` times, then sums the numbers at the grove offsets after 0.
+fn decrypt(message: &[isize], key: isize, rounds: usize) -> isize {
+    let mut mixer = CircularMixer::new(message.iter().map(|&n| n * key).collect());
+    for _ in 0..rounds {
+        mixer.mix();
     }
 
-    let message = message.into_iter().map(|(_, n)| n).collect::<Vec<_>>();
-
-    // Find the index of 0
-    let zero_index = message
-        .iter()
-        .enumerate()
-        .find(|(_, &n)| n == 0)
-        .map(|(i, _)| i)
-        .unwrap();
+    let zero = mixer.position(message.iter().position(|&n| n == 0).unwrap());
 
-    (1..=3)
-        .map(|n| message[wrapping_add(zero_index, n * 1000, message.len())])
+    GROVE_OFFSETS
+        .into_iter()
+        .map(|offset| mixer.get(zero + offset))
         .sum()
 }
 
 pub struct Day20;
 impl Day for Day20 {
-    type Input = Vec<isize>;
-    type Output = isize;
-
-    fn part_1(input: Self::Input) -> Self::Output {
-        let mut input: Vec<_> = input.into_iter().map(|n| (false, n)).collect();
-        let input_size = input.len() - 1;
+    const DAY: usize = 20;
 
-        while let Some(next_i) = {
-            let mut next_index = None;
-
-            for (i, (checked, _)) in input.iter().enumerate() {
-                if !checked {
-                    next_index = Some(i);
-                    break;
-                }
-            }
-
-            next_index
-        } {
-            // Get number
-            let (_, offset) = input.remove(next_i);
-
-            let mut new_index = ((input_size + next_i) as isize + (offset % input_size as isize))
-                as usize
-                % input_size;
+    type Input = Vec<isize>;
+    type Output1 = isize;
+    type Output2 = isize;
+    type Params = ();
 
-            if new_index == 0 {
-                new_index = input_size;
-            }
+    fn part_1(input: &Self::Input) -> Self::Output1 {
+        decrypt(input, 1, 1)
+    }
 
-            input.insert(new_index, (true, offset));
-        }
+    fn part_2(input: &Self::Input) -> Self::Output2 {
+        decrypt(input, DECRYPTION_KEY, 10)
+    }
 
-        let base_i = input
-            .iter()
-            .enumerate()
-            .find(|(_, &(_, n))| n == 0)
-            .map(|(i, _)| i)
-            .unwrap();
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
 
-        (1..=3)
-            .map(|n| input[(base_i + (n * 1000)) % input.len()].1)
-            .sum()
-    }
+        let message = raw
+            .lines()
+            .map(|n| src.number(n))
+            .collect::<Result<Vec<_>, _>>()?;
 
-    fn part_2(input: Self::Input) -> Self::Output {
-        mix(&input, 811589153, 10)
-    }
+        // The grove coordinates are found relative to 0
+        src.require(
+            message.contains(&0).then_some(()),
+            raw,
+            "a 0 in the message",
+        )?;
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.lines().map(|n| n.parse().unwrap()).collect()
+        Ok(message)
     }
 }
 
@@ -111,5 +68,5 @@ fn test() {
 0
 4
 ";
-    assert_eq!(Day20::run(input), (3, 1623178306));
+    assert_eq!(Day20::run(input), Ok((3, 1623178306)));
 }
@@ -1,9 +1,11 @@
-use crate::day::Day;
-use std::{
-    collections::{HashMap, VecDeque},
-    fmt::Debug,
-    ops::{Add, Div, Mul, Sub},
+use crate::{
+    day::Day,
+    parse::{ParseError, Source},
 };
+use std::{collections::HashMap, fmt::Display, ops::Neg};
+
+const ROOT: &str = "root";
+const HUMN: &str = "humn";
 
 #[derive(Clone)]
 pub enum Monkey {
@@ -18,391 +20,480 @@ pub struct Operation {
     operation: Op,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Op {
     Add,
     Sub,
     Mul,
     Div,
 }
-impl From<char> for Op {
-    fn from(value: char) -> Self {
+impl Op {
+    pub fn parse(src: &Source, raw: &str) -> Result<Self, ParseError> {
         use Op::*;
-        match value {
-            '+' => Add,
-            '-' => Sub,
-            '*' => Mul,
-            '/' => Div,
-            _ => unreachable!(),
+        match raw {
+            "+" => Ok(Add),
+            "-" => Ok(Sub),
+            "*" => Ok(Mul),
+            "/" => Ok(Div),
+            _ => Err(src.error(raw, "+, -, * or /")),
         }
     }
 }
 
-#[derive(Clone)]
-pub struct Polynomial {
-    parts: HashMap<isize, f64>,
+/// An exact fraction, kept in its lowest terms with a positive denominator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
 }
 
-impl Polynomial {
-    pub fn new() -> Self {
-        Polynomial {
-            parts: HashMap::new(),
-        }
-    }
-
-    pub fn derive(&self) -> Self {
-        let mut derivative = Self::new();
+impl Rational {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
 
-        for (&n, &x) in {
-            let mut powers = self.parts.iter().collect::<Vec<_>>();
-            powers.sort_by_key(|(&p, _)| p);
-
-            powers
-        } {
-            if n == 0 {
-                continue;
-            }
-
-            let next_n = n - 1;
-            let next_x = x * (n as f64);
-            derivative.parts.insert(next_n, next_x);
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
         }
 
-        derivative
-    }
+        let gcd = gcd(numerator, denominator) * denominator.signum();
 
-    pub fn solve(&self, x: f64) -> f64 {
-        self.parts
-            .iter()
-            .fold(0f64, |amount, (&n, b)| amount + (x.powf(n as f64) * b))
+        Some(Self {
+            numerator: numerator / gcd,
+            denominator: denominator / gcd,
+        })
     }
 
-    pub fn roots(&self) -> Option<f64> {
-        const MAX_ITERATIONS: usize = 10;
-        const EPSILON: f64 = 0.000001;
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
 
-        let derivative = self.derive();
-        let mut x0: f64 = 1.0;
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(
+            (self.numerator.checked_mul(rhs.denominator)?)
+                .checked_add(rhs.numerator.checked_mul(self.denominator)?)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
 
-        for _ in 0..MAX_ITERATIONS {
-            let y = self.solve(x0);
-            let y_prime = derivative.solve(x0);
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
 
-            if y_prime.abs() < EPSILON {
-                break;
-            }
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(rhs.numerator)?,
+            self.denominator.checked_mul(rhs.denominator)?,
+        )
+    }
 
-            let x1 = x0 - y / y_prime;
+    /// Returns `None` when dividing by zero, as well as on overflow.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(
+            self.numerator.checked_mul(rhs.denominator)?,
+            self.denominator.checked_mul(rhs.numerator)?,
+        )
+    }
+}
 
-            if (x1 - x0).abs() <= EPSILON {
-                return Some(x1);
-            }
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
 
-            x0 = x1;
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self {
+            numerator: n,
+            denominator: 1,
         }
-
-        None
     }
 }
 
-impl Add<&Self> for Polynomial {
+impl Neg for Rational {
     type Output = Self;
 
-    fn add(mut self, rhs: &Self) -> Self::Output {
-        for (&power, n) in rhs.parts.iter() {
-            *self.parts.entry(power).or_default() += n;
+    fn neg(self) -> Self::Output {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
         }
+    }
+}
 
-        self
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            denominator => write!(f, "{}/{denominator}", self.numerator),
+        }
     }
 }
 
-impl Sub<&Self> for Polynomial {
-    type Output = Self;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EquationError {
+    NoEquation,
+    NoUnknown,
+    NotLinear,
+    DivisionByZero,
+    Overflow,
+    NoSolution,
+    AnySolution,
+}
 
-    fn sub(mut self, rhs: &Self) -> Self::Output {
-        for (&power, n) in rhs.parts.iter() {
-            *self.parts.entry(power).or_default() -= n;
+impl Display for EquationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EquationError::NoEquation => write!(f, "root doesn't compare two monkeys"),
+            EquationError::NoUnknown => write!(f, "{HUMN} isn't part of the equation"),
+            EquationError::NotLinear => write!(f, "the equation is not linear in humn"),
+            EquationError::DivisionByZero => write!(f, "a monkey divides by zero"),
+            EquationError::Overflow => write!(f, "the numbers get too large to work with exactly"),
+            EquationError::NoSolution => write!(f, "no number satisfies the equation"),
+            EquationError::AnySolution => write!(f, "every number satisfies the equation"),
         }
-
-        self
     }
 }
 
-impl Mul<&Self> for Polynomial {
-    type Output = Self;
+/// `coefficient * humn + constant`, where `humn` is the number the human shouts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Linear {
+    coefficient: Rational,
+    constant: Rational,
+}
+
+impl Linear {
+    const HUMN: Self = Self {
+        coefficient: Rational {
+            numerator: 1,
+            denominator: 1,
+        },
+        constant: Rational::ZERO,
+    };
+
+    fn constant(constant: Rational) -> Self {
+        Self {
+            coefficient: Rational::ZERO,
+            constant,
+        }
+    }
 
-    fn mul(self, rhs: &Self) -> Self::Output {
-        let mut output = Polynomial::new();
+    fn scale(self, factor: Rational) -> Result<Self, EquationError> {
+        Ok(Self {
+            coefficient: checked(self.coefficient.checked_mul(factor))?,
+            constant: checked(self.constant.checked_mul(factor))?,
+        })
+    }
 
-        for (power, n) in &self.parts {
-            for (sub_power, sub_n) in &rhs.parts {
-                *output.parts.entry(power + sub_power).or_default() += n * sub_n;
+    fn apply(self, op: &Op, rhs: Self) -> Result<Self, EquationError> {
+        match op {
+            Op::Add => Ok(Self {
+                coefficient: checked(self.coefficient.checked_add(rhs.coefficient))?,
+                constant: checked(self.constant.checked_add(rhs.constant))?,
+            }),
+            Op::Sub => Ok(Self {
+                coefficient: checked(self.coefficient.checked_sub(rhs.coefficient))?,
+                constant: checked(self.constant.checked_sub(rhs.constant))?,
+            }),
+            Op::Mul if rhs.coefficient.is_zero() => self.scale(rhs.constant),
+            Op::Mul if self.coefficient.is_zero() => rhs.scale(self.constant),
+            Op::Div if rhs.coefficient.is_zero() => {
+                if rhs.constant.is_zero() {
+                    return Err(EquationError::DivisionByZero);
+                }
+
+                self.scale(checked(Rational::from(1).checked_div(rhs.constant))?)
             }
+            Op::Mul | Op::Div => Err(EquationError::NotLinear),
         }
+    }
 
-        // for (power, n) in &rhs.parts {
-        //     for (sub_power, sub_n) in &self.parts {
-        //         *output.parts.entry(power + sub_power).or_default() += n * sub_n;
-        //     }
-        // }
-
-        output
+    /// Finds the value of `humn` that makes this expression zero.
+    fn root(self) -> Result<Rational, EquationError> {
+        match (self.coefficient.is_zero(), self.constant.is_zero()) {
+            (true, true) => Err(EquationError::AnySolution),
+            (true, false) => Err(EquationError::NoSolution),
+            _ => checked((-self.constant).checked_div(self.coefficient)),
+        }
     }
 }
 
-impl Div<&Self> for Polynomial {
-    type Output = Self;
+/// Treats a failed checked operation as having overflowed.
+fn checked(value: Option<Rational>) -> Result<Rational, EquationError> {
+    value.ok_or(EquationError::Overflow)
+}
 
-    fn div(self, rhs: &Self) -> Self::Output {
-        let mut output = Polynomial::new();
+/// The number a monkey, or the human, shouts, or why it can't be worked out.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Shout(pub Result<Rational, EquationError>);
 
-        for (power, n) in &self.parts {
-            for (sub_power, sub_n) in &rhs.parts {
-                *output.parts.entry(power - sub_power).or_default() += n / sub_n;
-            }
+impl Display for Shout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Ok(humn) => write!(f, "{humn}"),
+            Err(e) => write!(f, "unsolvable, {e}"),
         }
+    }
+}
 
-        // for (power, n) in &rhs.parts {
-        //     for (sub_power, sub_n) in &self.parts {
-        //         *output.parts.entry(power + sub_power).or_default() += n / sub_n;
-        //     }
-        // }
+#[derive(Debug, PartialEq, Eq)]
+pub enum GraphError {
+    Duplicate(String),
+    Undefined {
+        name: String,
+        referenced_by: String,
+    },
+    /// Each monkey waits on the next, and the last is the same as the first.
+    Cycle(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Job {
+    Number(usize),
+    Operation(usize, Op, usize),
+}
 
-        output
+impl Job {
+    fn operands(&self) -> Option<[usize; 2]> {
+        match *self {
+            Job::Number(_) => None,
+            Job::Operation(lhs, _, rhs) => Some([lhs, rhs]),
+        }
     }
 }
 
-impl Debug for Polynomial {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut parts = self.parts.iter().collect::<Vec<_>>();
-        parts.sort_by_key(|(&p, _)| p);
-        parts.reverse();
-
-        write!(
-            f,
-            "{}",
-            parts
-                .into_iter()
-                .map(|(p, x)| {
-                    match p {
-                        0 => format!("{x}"),
-                        1 => format!("{x}x"),
-                        p => format!("{x}x^{p}"),
+/// Every monkey's job, along with an order to evaluate them in where each monkey comes after
+/// the monkeys it waits on.
+#[derive(Debug)]
+pub struct MonkeyGraph {
+    index: HashMap<String, usize>,
+    jobs: Vec<Job>,
+    order: Vec<usize>,
+}
+
+impl MonkeyGraph {
+    pub fn new(monkeys: Vec<(String, Monkey)>) -> Result<Self, GraphError> {
+        let mut index = HashMap::new();
+        for (i, (name, _)) in monkeys.iter().enumerate() {
+            if index.insert(name.clone(), i).is_some() {
+                return Err(GraphError::Duplicate(name.clone()));
+            }
+        }
+
+        let lookup = |name: &str, referenced_by: &str| {
+            index
+                .get(name)
+                .copied()
+                .ok_or_else(|| GraphError::Undefined {
+                    name: name.to_string(),
+                    referenced_by: referenced_by.to_string(),
+                })
+        };
+
+        let jobs = monkeys
+            .iter()
+            .map(|(name, monkey)| {
+                Ok(match monkey {
+                    Monkey::Number(n) => Job::Number(*n),
+                    Monkey::Operation(op) => {
+                        Job::Operation(lookup(&op.lhs, name)?, op.operation, lookup(&op.rhs, name)?)
                     }
                 })
-                .collect::<Vec<_>>()
-                .join(" + ")
-        )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let order = topological_order(&jobs).map_err(|cycle| {
+            GraphError::Cycle(cycle.into_iter().map(|i| monkeys[i].0.clone()).collect())
+        })?;
+
+        Ok(Self { index, jobs, order })
     }
-}
 
-pub struct Day21;
-impl Day for Day21 {
-    type Input = Vec<(String, Monkey)>;
-    type Output = usize;
+    /// Whether `name` is `monkey`, or anywhere in the tree of monkeys that it waits on.
+    pub fn depends_on(&self, monkey: &str, name: &str) -> bool {
+        let (Some(&start), Some(&target)) = (self.index.get(monkey), self.index.get(name)) else {
+            return false;
+        };
 
-    fn part_1(monkeys: Self::Input) -> Self::Output {
-        let mut known = HashMap::new();
-        let mut queue = VecDeque::new();
+        let mut visited = vec![false; self.jobs.len()];
+        let mut stack = vec![start];
 
-        for (id, monkey) in monkeys {
-            match monkey {
-                Monkey::Number(n) => {
-                    known.insert(id, n);
-                }
-                Monkey::Operation(op) => {
-                    queue.push_back((id, op));
-                }
+        while let Some(i) = stack.pop() {
+            if i == target {
+                return true;
             }
-        }
 
-        while let Some((id, op)) = queue.pop_front() {
-            // Attempt to find lhs and rhs
-            if let (Some(lhs), Some(rhs)) = (known.get(&op.lhs), known.get(&op.rhs)) {
-                known.insert(
-                    id,
-                    match op.operation {
-                        Op::Add => lhs + rhs,
-                        Op::Sub => lhs - rhs,
-                        Op::Mul => lhs * rhs,
-                        Op::Div => lhs / rhs,
-                    },
-                );
-            } else {
-                // Add to end to solve later
-                queue.push_back((id, op));
+            if !std::mem::replace(&mut visited[i], true) {
+                stack.extend(self.jobs[i].operands().into_iter().flatten());
             }
         }
 
-        *known.get("root").unwrap()
+        false
     }
 
-    fn part_2(monkeys: Self::Input) -> Self::Output {
-        let mut known: HashMap<String, usize> = HashMap::new();
-        let mut queue = VecDeque::new();
+    /// Works out what every monkey shouts, in terms of `unknown` if there is one.
+    fn expressions(&self, unknown: Option<usize>) -> Result<Vec<Linear>, EquationError> {
+        let mut values = vec![Linear::constant(Rational::ZERO); self.jobs.len()];
 
-        for (id, monkey) in monkeys {
-            if id == "humn" {
-                // Don't insert the human at all
-                continue;
-            }
+        for &i in &self.order {
+            values[i] = match self.jobs[i] {
+                _ if Some(i) == unknown => Linear::HUMN,
+                Job::Number(n) => Linear::constant(Rational::from(n as i128)),
+                Job::Operation(lhs, op, rhs) => values[lhs].apply(&op, values[rhs])?,
+            };
+        }
 
-            match monkey {
-                Monkey::Number(n) => {
-                    known.insert(id, n);
-                }
-                Monkey::Operation(op) => {
-                    if id == "root" {
-                        // Add root value
-                        known.insert(id.clone(), 0);
-
-                        // Add relation between root operands
-                        queue.push_back((
-                            op.rhs.clone(),
-                            Operation {
-                                lhs: op.lhs.clone(),
-                                operation: Op::Sub,
-                                rhs: id.clone(),
-                            },
-                        ));
-                        queue.push_back((
-                            op.lhs,
-                            Operation {
-                                lhs: op.rhs,
-                                operation: Op::Sub,
-                                rhs: id,
-                            },
-                        ));
-                    } else {
-                        queue.push_back((id.clone(), op.clone()));
-                        match op.operation {
-                            Op::Add => [
-                                (
-                                    op.lhs.clone(),
-                                    Operation {
-                                        lhs: id.clone(),
-                                        rhs: op.rhs.clone(),
-                                        operation: Op::Sub,
-                                    },
-                                ),
-                                (
-                                    op.rhs,
-                                    Operation {
-                                        lhs: id,
-                                        rhs: op.lhs,
-                                        operation: Op::Sub,
-                                    },
-                                ),
-                            ],
-                            Op::Sub => [
-                                (
-                                    op.lhs.clone(),
-                                    Operation {
-                                        lhs: id.clone(),
-                                        rhs: op.rhs.clone(),
-                                        operation: Op::Add,
-                                    },
-                                ),
-                                (
-                                    op.rhs,
-                                    Operation {
-                                        lhs: op.lhs,
-                                        rhs: id,
-                                        operation: Op::Sub,
-                                    },
-                                ),
-                            ],
-                            Op::Mul => [
-                                (
-                                    op.lhs.clone(),
-                                    Operation {
-                                        lhs: id.clone(),
-                                        rhs: op.rhs.clone(),
-                                        operation: Op::Div,
-                                    },
-                                ),
-                                (
-                                    op.rhs,
-                                    Operation {
-                                        lhs: id,
-                                        rhs: op.lhs,
-                                        operation: Op::Div,
-                                    },
-                                ),
-                            ],
-                            Op::Div => [
-                                (
-                                    op.lhs.clone(),
-                                    Operation {
-                                        lhs: id.clone(),
-                                        rhs: op.rhs.clone(),
-                                        operation: Op::Mul,
-                                    },
-                                ),
-                                (
-                                    op.rhs,
-                                    Operation {
-                                        lhs: op.lhs,
-                                        rhs: id,
-                                        operation: Op::Div,
-                                    },
-                                ),
-                            ],
-                        }
-                        .into_iter()
-                        .for_each(|o| queue.push_back(o));
-                    }
-                }
-            }
+        Ok(values)
+    }
+}
+
+/// Orders the jobs so that each comes after its operands, otherwise returns a cycle.
+fn topological_order(jobs: &[Job]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut waiting = vec![0; jobs.len()];
+    let mut dependents = vec![Vec::new(); jobs.len()];
+
+    for (i, job) in jobs.iter().enumerate() {
+        for operand in job.operands().into_iter().flatten() {
+            waiting[i] += 1;
+            dependents[operand].push(i);
         }
+    }
 
-        while let Some((id, op)) = queue.pop_front() {
-            if let (Some(lhs), Some(rhs)) = (known.get(&op.lhs), known.get(&op.rhs)) {
-                dbg!(&id, &lhs, &rhs, &op);
-                let result = match op.operation {
-                    Op::Add => lhs + rhs,
-                    Op::Sub => lhs - rhs,
-                    Op::Mul => lhs * rhs,
-                    Op::Div => lhs / rhs,
-                };
-
-                if id == "humn" {
-                    return result;
-                }
+    let mut ready = (0..jobs.len())
+        .filter(|&i| waiting[i] == 0)
+        .collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(jobs.len());
+
+    while let Some(i) = ready.pop() {
+        order.push(i);
 
-                known.insert(id, result);
-            } else {
-                queue.push_back(dbg!(id, op));
+        for &dependent in &dependents[i] {
+            waiting[dependent] -= 1;
+            if waiting[dependent] == 0 {
+                ready.push(dependent);
             }
         }
+    }
+
+    if order.len() == jobs.len() {
+        return Ok(order);
+    }
+
+    // Anything left is waiting on a cycle, so follow the operands that are also left over until
+    // one repeats
+    let mut path = Vec::new();
+    let mut i = (0..jobs.len()).find(|&i| waiting[i] > 0).unwrap();
+
+    while !path.contains(&i) {
+        path.push(i);
+        i = jobs[i]
+            .operands()
+            .into_iter()
+            .flatten()
+            .find(|&operand| waiting[operand] > 0)
+            .unwrap();
+    }
+
+    let start = path.iter().position(|&j| j == i).unwrap();
+    path.push(i);
+
+    Err(path.split_off(start))
+}
+
+pub struct Day21;
+impl Day for Day21 {
+    const DAY: usize = 21;
+
+    type Input = MonkeyGraph;
+    type Output1 = Shout;
+    type Output2 = Shout;
+    type Params = ();
+
+    fn part_1(graph: &Self::Input) -> Self::Output1 {
+        let root = graph.index[ROOT];
+
+        Shout(graph.expressions(None).map(|values| values[root].constant))
+    }
+
+    fn part_2(graph: &Self::Input) -> Self::Output2 {
+        // Both sides of root's equation are equal, so their difference is zero
+        let Job::Operation(lhs, _, rhs) = graph.jobs[graph.index[ROOT]] else {
+            return Shout(Err(EquationError::NoEquation));
+        };
+
+        if !graph.depends_on(ROOT, HUMN) {
+            return Shout(Err(EquationError::NoUnknown));
+        }
 
-        *known.get("humn").unwrap()
+        Shout(
+            graph
+                .expressions(Some(graph.index[HUMN]))
+                .and_then(|values| values[lhs].apply(&Op::Sub, values[rhs])?.root()),
+        )
     }
 
-    fn parse(raw: &str) -> Self::Input {
-        raw.lines()
-            .map(|line| {
-                let (id, rhs) = line.split_once(": ").unwrap();
+    fn parse(raw: &str, _: &Self::Params) -> Result<Self::Input, ParseError> {
+        let src = Source::new(Self::DAY, raw);
 
-                let rhs = rhs.split_whitespace().collect::<Vec<_>>();
+        let lines = raw
+            .lines()
+            .map(|line| src.split_once(line, ": "))
+            .collect::<Result<Vec<_>, _>>()?;
 
-                (
+        let monkeys = lines
+            .iter()
+            .map(|&(id, rhs)| {
+                let parts = rhs.split_whitespace().collect::<Vec<_>>();
+
+                Ok((
                     id.to_string(),
-                    if rhs.len() == 3 {
-                        // Operation
-                        Monkey::Operation(Operation {
-                            lhs: rhs[0].to_string(),
-                            rhs: rhs[2].to_string(),
-                            operation: Op::from(rhs[1].chars().next().unwrap()),
-                        })
-                    } else {
-                        // Number
-                        Monkey::Number(rhs[0].parse().unwrap())
+                    match parts[..] {
+                        [lhs, operation, rhs] => Monkey::Operation(Operation {
+                            lhs: lhs.to_string(),
+                            rhs: rhs.to_string(),
+                            operation: Op::parse(&src, operation)?,
+                        }),
+                        [number] => Monkey::Number(src.number(number)?),
+                        _ => return Err(src.error(rhs, "a number or an operation")),
                     },
-                )
+                ))
             })
-            .collect()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        src.require(
+            lines.iter().find(|(id, _)| *id == ROOT),
+            raw,
+            format!("a monkey named {ROOT}"),
+        )?;
+
+        let line = |name: &str| lines.iter().find(|(id, _)| *id == name).unwrap();
+
+        MonkeyGraph::new(monkeys).map_err(|e| match e {
+            GraphError::Duplicate(name) => {
+                let (id, _) = lines.iter().filter(|(id, _)| *id == name).nth(1).unwrap();
+                src.error(id, "a name that isn't already taken")
+            }
+            GraphError::Undefined {
+                name,
+                referenced_by,
+            } => {
+                let (_, rhs) = line(&referenced_by);
+                let operand = rhs.split_whitespace().find(|&operand| operand == name);
+                src.error(operand.unwrap(), "a monkey that exists")
+            }
+            GraphError::Cycle(cycle) => src.error(
+                line(&cycle[0]).0,
+                format!(
+                    "monkeys that don't wait on themselves: {}",
+                    cycle.join(" -> ")
+                ),
+            ),
+        })
     }
 }
 
@@ -424,5 +515,54 @@ lgvd: ljgn * ptdq
 drzm: hmdt - zczc
 hmdt: 32";
 
-    assert_eq!(Day21::run(input), (152, 301));
+    assert_eq!(
+        Day21::run(input),
+        Ok((
+            Shout(Ok(Rational::from(152))),
+            Shout(Ok(Rational::from(301)))
+        ))
+    );
+
+    let graph = Day21::parse(input, &()).unwrap();
+    assert!(graph.depends_on("pppw", HUMN));
+    assert!(!graph.depends_on("sjmn", HUMN));
+    assert!(graph.depends_on(HUMN, HUMN));
+
+    let input = "root: aaaa + bbbb
+aaaa: humn * humn
+bbbb: 4
+humn: 2";
+    assert_eq!(
+        Day21::part_2(&Day21::parse(input, &()).unwrap()),
+        Shout(Err(EquationError::NotLinear))
+    );
+
+    let input = "root: aaaa + humn
+aaaa: bbbb * bbbb
+bbbb: cccc * cccc
+cccc: dddd * dddd
+dddd: 100000
+humn: 1";
+    assert_eq!(
+        Day21::run(input),
+        Ok((
+            Shout(Err(EquationError::Overflow)),
+            Shout(Err(EquationError::Overflow))
+        ))
+    );
+
+    let error = Day21::parse("root: aaaa + bbbb\naaaa: 4", &()).unwrap_err();
+    assert_eq!((error.line, error.column), (1, 14));
+    assert_eq!(error.expected, "a monkey that exists");
+
+    let error = Day21::parse(
+        "root: aaaa + cccc\naaaa: bbbb * cccc\nbbbb: cccc + aaaa\ncccc: 3",
+        &(),
+    )
+    .unwrap_err();
+    assert_eq!(error.line, 2);
+    assert_eq!(
+        error.expected,
+        "monkeys that don't wait on themselves: aaaa -> bbbb -> aaaa"
+    );
 }
@@ -1,3 +1,5 @@
+use crate::registry::Registry;
+
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
@@ -19,3 +21,28 @@ pub mod day_18;
 pub mod day_19;
 pub mod day_20;
 pub mod day_21;
+
+pub fn registry() -> Registry {
+    Registry::new()
+        .with::<day_01::Day01>()
+        .with::<day_02::Day02>()
+        .with::<day_03::Day03>()
+        .with::<day_04::Day04>()
+        .with::<day_05::Day05>()
+        .with::<day_06::Day06>()
+        .with::<day_07::Day07>()
+        .with::<day_08::Day08>()
+        .with::<day_09::Day09>()
+        .with::<day_10::Day10>()
+        .with::<day_11::Day11>()
+        .with::<day_12::Day12>()
+        .with::<day_13::Day13>()
+        .with::<day_14::Day14>()
+        .with::<day_15::Day15>()
+        .with::<day_16::Day16>()
+        .with::<day_17::Day17>()
+        .with::<day_18::Day18>()
+        .with::<day_19::Day19>()
+        .with::<day_20::Day20>()
+        .with::<day_21::Day21>()
+}
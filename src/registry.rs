@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap, fmt::Display, hint::black_box, marker::PhantomData, time::Duration,
+};
+
+use crate::{
+    day::{Day, ParamError, Params, Part},
+    parse::ParseError,
+    timing::{time, Benchmark, Stats, Timings},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outputs {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RunError {
+    Param { day: usize, error: ParamError },
+    Parse(ParseError),
+}
+
+impl RunError {
+    pub fn report(&self) -> String {
+        match self {
+            RunError::Param { day, error } => format!("error: day {day:02}: {error}"),
+            RunError::Parse(e) => e.report(),
+        }
+    }
+}
+
+impl From<ParseError> for RunError {
+    fn from(e: ParseError) -> Self {
+        RunError::Parse(e)
+    }
+}
+
+/// Parameters given by name, such as from the command line, as `(name, value)` pairs.
+pub type Overrides = [(String, String)];
+
+/// Type-erased handle to a [`Day`], so that days can be looked up and run by number.
+pub trait Runner {
+    fn day(&self) -> usize;
+
+    /// Runs the requested part against `input`, or both parts if no part is given.
+    fn run(
+        &self,
+        input: &str,
+        part: Option<Part>,
+        overrides: &Overrides,
+    ) -> Result<(Outputs, Timings), RunError>;
+
+    /// Repeats each stage `iterations` times, discarding the outputs.
+    fn bench(
+        &self,
+        input: &str,
+        part: Option<Part>,
+        overrides: &Overrides,
+        iterations: usize,
+    ) -> Result<Benchmark, RunError>;
+}
+
+struct DayRunner<D>(PhantomData<D>);
+
+/// Starts from the day's defaults, replacing any parameters that have been overridden.
+fn params<D: Day>(overrides: &Overrides) -> Result<D::Params, RunError> {
+    let mut params = D::Params::default();
+
+    for (name, value) in overrides {
+        params
+            .set(name, value)
+            .map_err(|error| RunError::Param { day: D::DAY, error })?;
+    }
+
+    Ok(params)
+}
+
+/// Runs a single part if it was selected, rendering its output outside of the timer.
+fn run_part<I, O: Display>(
+    selected: Part,
+    part: Option<Part>,
+    input: &I,
+    f: fn(&I) -> O,
+) -> (Option<String>, Option<Duration>) {
+    if part.is_some_and(|part| part != selected) {
+        return (None, None);
+    }
+
+    let (output, duration) = time(|| f(input));
+
+    (Some(output.to_string()), Some(duration))
+}
+
+fn bench_part<I, O>(
+    selected: Part,
+    part: Option<Part>,
+    input: &I,
+    f: fn(&I) -> O,
+    iterations: usize,
+) -> Option<Stats> {
+    if part.is_some_and(|part| part != selected) {
+        return None;
+    }
+
+    Some(Stats::from_samples(
+        (0..iterations)
+            .map(|_| time(|| black_box(f(input))).1)
+            .collect(),
+    ))
+}
+
+impl<D> Runner for DayRunner<D>
+where
+    D: Day,
+    D::Output1: Display,
+    D::Output2: Display,
+{
+    fn day(&self) -> usize {
+        D::DAY
+    }
+
+    fn run(
+        &self,
+        input: &str,
+        part: Option<Part>,
+        overrides: &Overrides,
+    ) -> Result<(Outputs, Timings), RunError> {
+        let params = params::<D>(overrides)?;
+
+        let (input, parse) = time(|| D::parse(input, &params));
+        let input = input?;
+
+        let (part_1, part_1_duration) = run_part(Part::One, part, &input, D::part_1);
+        let (part_2, part_2_duration) = run_part(Part::Two, part, &input, D::part_2);
+
+        Ok((
+            Outputs { part_1, part_2 },
+            Timings {
+                parse,
+                part_1: part_1_duration,
+                part_2: part_2_duration,
+            },
+        ))
+    }
+
+    fn bench(
+        &self,
+        raw: &str,
+        part: Option<Part>,
+        overrides: &Overrides,
+        iterations: usize,
+    ) -> Result<Benchmark, RunError> {
+        let params = params::<D>(overrides)?;
+        let input = D::parse(raw, &params)?;
+
+        let parse = Stats::from_samples(
+            (0..iterations)
+                .map(|_| time(|| black_box(D::parse(raw, &params))).1)
+                .collect(),
+        );
+
+        Ok(Benchmark {
+            parse,
+            part_1: bench_part(Part::One, part, &input, D::part_1, iterations),
+            part_2: bench_part(Part::Two, part, &input, D::part_2, iterations),
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    days: BTreeMap<usize, Box<dyn Runner>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<D>(mut self) -> Self
+    where
+        D: Day + 'static,
+        D::Output1: Display,
+        D::Output2: Display,
+    {
+        let previous = self
+            .days
+            .insert(D::DAY, Box::new(DayRunner::<D>(PhantomData)));
+        assert!(previous.is_none(), "day {} registered twice", D::DAY);
+
+        self
+    }
+
+    pub fn get(&self, day: usize) -> Option<&dyn Runner> {
+        self.days.get(&day).map(|runner| runner.as_ref())
+    }
+
+    pub fn days(&self) -> Vec<usize> {
+        self.days.keys().copied().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Runner> {
+        self.days.values().map(|runner| runner.as_ref())
+    }
+}
+
+#[test]
+fn test() {
+    use crate::problems::day_01::Day01;
+
+    let registry = Registry::new().with::<Day01>();
+    let input = "1000\n\n2000\n\n3000\n\n4000";
+
+    assert_eq!(registry.days(), vec![1]);
+    assert!(registry.get(2).is_none());
+
+    let day = registry.get(1).unwrap();
+    assert_eq!(
+        day.run(input, None, &[]).map(|(outputs, _)| outputs),
+        Ok(Outputs {
+            part_1: Some("4000".to_string()),
+            part_2: Some("9000".to_string()),
+        })
+    );
+    assert_eq!(
+        day.run(input, Some(Part::Two), &[])
+            .map(|(outputs, _)| outputs),
+        Ok(Outputs {
+            part_1: None,
+            part_2: Some("9000".to_string()),
+        })
+    );
+    assert_eq!(
+        day.run(input, None, &[("row".to_string(), "10".to_string())])
+            .map(|(outputs, _)| outputs),
+        Err(RunError::Param {
+            day: 1,
+            error: ParamError::Unknown("row".to_string())
+        })
+    );
+}
@@ -0,0 +1,241 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Anything that can be searched, described by the nodes one step away from each node along
+/// with the cost of taking that step. Closures returning an iterator of `(node, cost)` pairs
+/// are graphs.
+pub trait Graph<N> {
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, usize)>;
+}
+
+impl<N, F, I> Graph<N> for F
+where
+    F: Fn(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    fn neighbours(&self, node: &N) -> impl Iterator<Item = (N, usize)> {
+        self(node).into_iter()
+    }
+}
+
+/// A route through a graph, from one of the starting nodes to the goal, inclusive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: usize,
+    pub nodes: Vec<N>,
+}
+
+/// The cheapest known cost of reaching each node, and the node it was reached from.
+type Explored<N> = HashMap<N, (usize, Option<N>)>;
+
+impl<N: Clone + Eq + Hash> Path<N> {
+    fn trace(explored: &Explored<N>, goal: N) -> Self {
+        let cost = explored[&goal].0;
+        let mut nodes = vec![goal];
+
+        while let Some(previous) = &explored[nodes.last().unwrap()].1 {
+            nodes.push(previous.clone());
+        }
+        nodes.reverse();
+
+        Self { cost, nodes }
+    }
+}
+
+/// Explores outwards from `starts` one step at a time, stopping early at the first node
+/// matching `is_goal`. Every step must cost 1.
+fn breadth_first<N, G>(
+    graph: &G,
+    starts: impl IntoIterator<Item = N>,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Explored<N>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let mut explored = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if explored.insert(start.clone(), (0, None)).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return (explored, Some(node));
+        }
+
+        let cost = explored[&node].0;
+        for (next, step) in graph.neighbours(&node) {
+            debug_assert_eq!(step, 1, "breadth first search needs every step to cost 1");
+
+            if !explored.contains_key(&next) {
+                explored.insert(next.clone(), (cost + 1, Some(node.clone())));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (explored, None)
+}
+
+/// Finds the shortest path from any of `starts` to a node matching `is_goal`, in a graph where
+/// every step costs 1.
+pub fn bfs<N, G>(
+    graph: &G,
+    starts: impl IntoIterator<Item = N>,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let (explored, goal) = breadth_first(graph, starts, is_goal);
+
+    goal.map(|goal| Path::trace(&explored, goal))
+}
+
+/// The distance to every node that can be reached from `starts`, in a graph where every step
+/// costs 1.
+pub fn reachable<N, G>(graph: &G, starts: impl IntoIterator<Item = N>) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let (explored, _) = breadth_first(graph, starts, |_| false);
+
+    explored
+        .into_iter()
+        .map(|(node, (cost, _))| (node, cost))
+        .collect()
+}
+
+/// Finds the cheapest path from any of `starts` to a node matching `is_goal`.
+#[allow(dead_code)]
+pub fn dijkstra<N, G>(
+    graph: &G,
+    starts: impl IntoIterator<Item = N>,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    a_star(graph, starts, is_goal, |_| 0)
+}
+
+/// Finds the cheapest path from any of `starts` to a node matching `is_goal`, exploring nodes
+/// that look closer first. `heuristic` must never overestimate the remaining cost, or a more
+/// expensive path may be returned.
+pub fn a_star<N, G>(
+    graph: &G,
+    starts: impl IntoIterator<Item = N>,
+    mut is_goal: impl FnMut(&N) -> bool,
+    mut heuristic: impl FnMut(&N) -> usize,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    G: Graph<N>,
+{
+    let mut explored = HashMap::new();
+
+    // Nodes aren't necessarily ordered, so the heap refers to them by their index here
+    let mut queued = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        explored.insert(start.clone(), (0, None));
+        heap.push(Reverse((heuristic(&start), 0, queued.len())));
+        queued.push(start);
+    }
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        let node = &queued[i];
+
+        // A cheaper way here was found after this one was queued
+        if explored[node].0 < cost {
+            continue;
+        }
+
+        if is_goal(node) {
+            return Some(Path::trace(&explored, node.clone()));
+        }
+
+        let node = node.clone();
+        for (next, step) in graph.neighbours(&node) {
+            let next_cost = cost + step;
+            if explored
+                .get(&next)
+                .is_some_and(|&(known, _)| known <= next_cost)
+            {
+                continue;
+            }
+
+            explored.insert(next.clone(), (next_cost, Some(node.clone())));
+            heap.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                queued.len(),
+            )));
+            queued.push(next);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test() {
+    // A line of towns from 0 to 9, with a pricey shortcut from 2 to 7
+    let roads = |&town: &usize| {
+        [
+            town.checked_sub(1).map(|previous| (previous, 1)),
+            Some(town + 1)
+                .filter(|&next| next < 10)
+                .map(|next| (next, 1)),
+            (town == 2).then_some((7, 4)),
+        ]
+        .into_iter()
+        .flatten()
+    };
+    let path = |towns: &[usize], cost| {
+        Some(Path {
+            cost,
+            nodes: towns.to_vec(),
+        })
+    };
+
+    assert_eq!(
+        dijkstra(&roads, [0], |&town| town == 8),
+        path(&[0, 1, 2, 7, 8], 7)
+    );
+    assert_eq!(
+        a_star(
+            &roads,
+            [0],
+            |&town| town == 8,
+            |&town| 8usize.abs_diff(town)
+        ),
+        path(&[0, 1, 2, 7, 8], 7)
+    );
+    assert_eq!(dijkstra(&roads, [0], |&town| town == 10), None);
+
+    // Breadth first search only counts steps, so the shortcut is always worth taking
+    let steps = |&town: &usize| roads(&town).map(|(next, _)| (next, 1));
+    assert_eq!(
+        bfs(&steps, [0], |&town| town == 6),
+        path(&[0, 1, 2, 7, 6], 4)
+    );
+    assert_eq!(
+        bfs(&steps, [9, 0], |&town| town == 4),
+        path(&[0, 1, 2, 3, 4], 4)
+    );
+    assert_eq!(bfs(&steps, [3], |&town| town == 3), path(&[3], 0));
+    assert_eq!(reachable(&steps, [4])[&0], 4);
+    assert_eq!(reachable(&steps, [4]).len(), 10);
+}
@@ -0,0 +1,172 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let output = f();
+
+    (output, start.elapsed())
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part_1: Option<Duration>,
+    pub part_2: Option<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_1.unwrap_or_default() + self.part_2.unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is required");
+
+        samples.sort_unstable();
+
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Benchmark {
+    pub parse: Stats,
+    pub part_1: Option<Stats>,
+    pub part_2: Option<Stats>,
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration
+        .map(|duration| format!("{duration:.2?}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub fn timings_table(rows: &[(usize, Timings)]) -> String {
+    let mut table = String::new();
+    writeln!(
+        table,
+        "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    )
+    .unwrap();
+    writeln!(
+        table,
+        "{:-<4}+{:-<12}+{:-<12}+{:-<12}+{:-<11}",
+        "", "", "", "", ""
+    )
+    .unwrap();
+
+    for (day, timings) in rows {
+        writeln!(
+            table,
+            "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}",
+            format!("{day:02}"),
+            format_duration(Some(timings.parse)),
+            format_duration(timings.part_1),
+            format_duration(timings.part_2),
+            format_duration(Some(timings.total())),
+        )
+        .unwrap();
+    }
+
+    let total = rows.iter().map(|(_, timings)| timings.total()).sum();
+    writeln!(
+        table,
+        "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}",
+        "",
+        "",
+        "",
+        "",
+        format_duration(Some(total))
+    )
+    .unwrap();
+
+    table
+}
+
+pub fn benchmark_table(rows: &[(usize, Benchmark)], iterations: usize) -> String {
+    let mut table = String::new();
+    writeln!(table, "{iterations} iterations").unwrap();
+    writeln!(
+        table,
+        "{:>3} | {:<6} | {:>10} | {:>10} | {:>10}",
+        "Day", "Stage", "Min", "Median", "Max"
+    )
+    .unwrap();
+    writeln!(
+        table,
+        "{:-<4}+{:-<8}+{:-<12}+{:-<12}+{:-<11}",
+        "", "", "", "", ""
+    )
+    .unwrap();
+
+    for (day, benchmark) in rows {
+        let stages = [
+            ("parse", Some(benchmark.parse)),
+            ("part 1", benchmark.part_1),
+            ("part 2", benchmark.part_2),
+        ];
+
+        for (stage, stats) in stages {
+            if let Some(stats) = stats {
+                writeln!(
+                    table,
+                    "{:>3} | {:<6} | {:>10} | {:>10} | {:>10}",
+                    format!("{day:02}"),
+                    stage,
+                    format_duration(Some(stats.min)),
+                    format_duration(Some(stats.median)),
+                    format_duration(Some(stats.max)),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    table
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+
+    assert_eq!(
+        Stats::from_samples(vec![ms(5), ms(1), ms(3)]),
+        Stats {
+            min: ms(1),
+            median: ms(3),
+            max: ms(5),
+        }
+    );
+    assert_eq!(
+        Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]),
+        Stats {
+            min: ms(1),
+            median: ms(3),
+            max: ms(9),
+        }
+    );
+}